@@ -1,28 +1,47 @@
-use bevy::{color::palettes, math::vec2, prelude::*};
-use bevy_ed2d::Ed2dPlugin;
+use bevy::{
+    color::palettes,
+    input::{keyboard::KeyboardInput, mouse::MouseButtonInput},
+    log::LogPlugin,
+    math::vec2,
+    prelude::*,
+};
+use bevy_ed2d::{log_layer, Ed2dPlugin, MonitorEventAppExt};
 use bevy_mod_picking::PickableBundle;
 
 fn main() {
     App::new()
         .insert_resource(ClearColor(palettes::tailwind::NEUTRAL_800.into()))
-        .add_plugins((DefaultPlugins, Ed2dPlugin::default()))
+        .add_plugins((
+            DefaultPlugins.set(LogPlugin {
+                // show logs in the editor's Log tab
+                custom_layer: log_layer,
+                ..default()
+            }),
+            Ed2dPlugin::default(),
+        ))
+        // show input events in the editor's Events tab
+        .monitor_event::<KeyboardInput>()
+        .monitor_event::<MouseButtonInput>()
         .add_systems(Startup, setup)
         .run();
 }
 
 fn setup(mut commands: Commands) {
-    commands.spawn((
-        Name::new("Blue square"),
-        SpriteBundle {
-            sprite: Sprite {
-                color: palettes::tailwind::BLUE_500.into(),
-                custom_size: Some(vec2(100., 100.)),
+    let square = commands
+        .spawn((
+            Name::new("Blue square"),
+            SpriteBundle {
+                sprite: Sprite {
+                    color: palettes::tailwind::BLUE_500.into(),
+                    custom_size: Some(vec2(100., 100.)),
+                    ..default()
+                },
                 ..default()
             },
-            ..default()
-        },
-        PickableBundle::default(),
-    ));
+            PickableBundle::default(),
+        ))
+        .id();
+    info!("Spawned square {square}");
 
     commands.spawn((
         Name::new("Red rectangle"),
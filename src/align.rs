@@ -0,0 +1,189 @@
+use bevy::{prelude::*, render::primitives::Aabb, utils::HashSet};
+use egui_dock::egui;
+
+#[derive(Clone, Copy)]
+pub(crate) enum Alignment {
+    Left,
+    HorizontalCenter,
+    Right,
+    Top,
+    VerticalCenter,
+    Bottom,
+    DistributeHorizontally,
+    DistributeVertically,
+}
+
+/// Axis-aligned world space bounds of an entity, enclosing its `Aabb` when rotated
+pub(crate) fn entity_bounds(aabb: &Aabb, transform: &GlobalTransform) -> Rect {
+    let center = Vec3::from(aabb.center);
+    let half_extents = Vec3::from(aabb.half_extents);
+    [
+        Vec3::new(-1., -1., 0.),
+        Vec3::new(1., -1., 0.),
+        Vec3::new(1., 1., 0.),
+        Vec3::new(-1., 1., 0.),
+    ]
+    .into_iter()
+    .map(|corner| {
+        transform
+            .transform_point(center + corner * half_extents)
+            .xy()
+    })
+    .fold(
+        Rect::from_center_size(transform.transform_point(center).xy(), Vec2::ZERO),
+        |bounds, corner| bounds.union_point(corner),
+    )
+}
+
+/// Shows the align and distribute buttons, returning the one that was clicked
+pub(crate) fn align_menu_ui(ui: &mut egui::Ui) -> Option<Alignment> {
+    let mut clicked = None;
+    ui.menu_button("Align", |ui| {
+        let buttons = [
+            ("Left edges", Alignment::Left),
+            ("Horizontal centers", Alignment::HorizontalCenter),
+            ("Right edges", Alignment::Right),
+            ("Top edges", Alignment::Top),
+            ("Vertical centers", Alignment::VerticalCenter),
+            ("Bottom edges", Alignment::Bottom),
+        ];
+        for (label, alignment) in buttons {
+            if ui.button(label).clicked() {
+                clicked = Some(alignment);
+            }
+        }
+
+        ui.separator();
+
+        let buttons = [
+            ("Distribute horizontally", Alignment::DistributeHorizontally),
+            ("Distribute vertically", Alignment::DistributeVertically),
+        ];
+        for (label, alignment) in buttons {
+            if ui
+                .button(label)
+                .on_hover_text("Spaces the selection evenly between the outermost entities")
+                .clicked()
+            {
+                clicked = Some(alignment);
+            }
+        }
+
+        if clicked.is_some() {
+            ui.close_menu();
+        }
+    });
+    clicked
+}
+
+pub(crate) fn align(
+    world: &mut World,
+    entities: impl Iterator<Item = Entity>,
+    alignment: Alignment,
+) {
+    let entities: HashSet<Entity> = entities.collect();
+
+    let mut bounds: Vec<(Entity, Rect)> = entities
+        .iter()
+        .copied()
+        // children move along with their parent, so they'd be moved twice
+        .filter(|&entity| !has_ancestor_in(world, entity, &entities))
+        .filter_map(|entity| {
+            let entity_ref = world.get_entity(entity)?;
+            let bounds = entity_bounds(
+                entity_ref.get::<Aabb>()?,
+                entity_ref.get::<GlobalTransform>()?,
+            );
+            Some((entity, bounds))
+        })
+        .collect();
+
+    if bounds.len() < 2 {
+        return;
+    }
+
+    let selection = bounds
+        .iter()
+        .map(|(_, bounds)| *bounds)
+        .reduce(|a, b| a.union(b))
+        .unwrap_or_default();
+
+    let moves = match alignment {
+        Alignment::Left => offsets(&bounds, |b| Vec2::X * (selection.min.x - b.min.x)),
+        Alignment::HorizontalCenter => {
+            offsets(&bounds, |b| Vec2::X * (selection.center().x - b.center().x))
+        }
+        Alignment::Right => offsets(&bounds, |b| Vec2::X * (selection.max.x - b.max.x)),
+        Alignment::Top => offsets(&bounds, |b| Vec2::Y * (selection.max.y - b.max.y)),
+        Alignment::VerticalCenter => {
+            offsets(&bounds, |b| Vec2::Y * (selection.center().y - b.center().y))
+        }
+        Alignment::Bottom => offsets(&bounds, |b| Vec2::Y * (selection.min.y - b.min.y)),
+        Alignment::DistributeHorizontally => distribute(&mut bounds, Vec2::X),
+        Alignment::DistributeVertically => distribute(&mut bounds, Vec2::Y),
+    };
+
+    for (entity, offset) in moves {
+        move_in_world(world, entity, offset);
+    }
+}
+
+fn offsets(bounds: &[(Entity, Rect)], offset: impl Fn(&Rect) -> Vec2) -> Vec<(Entity, Vec2)> {
+    bounds
+        .iter()
+        .map(|(entity, bounds)| (*entity, offset(bounds)))
+        .collect()
+}
+
+/// Moves the entities along `axis` so the gaps between them are equal
+fn distribute(bounds: &mut [(Entity, Rect)], axis: Vec2) -> Vec<(Entity, Vec2)> {
+    bounds.sort_by(|(_, a), (_, b)| a.center().dot(axis).total_cmp(&b.center().dot(axis)));
+
+    let (Some((_, first)), Some((_, last))) = (bounds.first(), bounds.last()) else {
+        return Vec::new();
+    };
+    let span = last.max.dot(axis) - first.min.dot(axis);
+    let total_size: f32 = bounds.iter().map(|(_, b)| b.size().dot(axis)).sum();
+    let gap = (span - total_size) / (bounds.len() - 1) as f32;
+
+    let mut position = first.min.dot(axis);
+    bounds
+        .iter()
+        .map(|(entity, b)| {
+            let offset = position - b.min.dot(axis);
+            position += b.size().dot(axis) + gap;
+            (*entity, axis * offset)
+        })
+        .collect()
+}
+
+fn has_ancestor_in(world: &World, entity: Entity, entities: &HashSet<Entity>) -> bool {
+    let mut current = entity;
+    while let Some(parent) = world.get::<Parent>(current) {
+        current = parent.get();
+        if entities.contains(&current) {
+            return true;
+        }
+    }
+    false
+}
+
+/// Moves the entity by `offset` in world space, taking its parent's transform into account
+fn move_in_world(world: &mut World, entity: Entity, offset: Vec2) {
+    let parent_transform = world
+        .get::<Parent>(entity)
+        .and_then(|parent| world.get::<GlobalTransform>(parent.get()))
+        .copied();
+    let Some(global_transform) = world.get::<GlobalTransform>(entity).copied() else {
+        return;
+    };
+    let Some(mut transform) = world.get_mut::<Transform>(entity) else {
+        return;
+    };
+
+    let target = global_transform.translation() + offset.extend(0.);
+    transform.translation = match parent_transform {
+        Some(parent) => parent.affine().inverse().transform_point3(target),
+        None => target,
+    };
+}
@@ -0,0 +1,332 @@
+use crate::{
+    assets::{self, AssetDrag},
+    InspectorSelection,
+};
+use bevy::{
+    asset::{
+        io::AssetSourceId, AssetPath, LoadedUntypedAsset, ReflectAsset, ReflectHandle,
+        UntypedAssetId,
+    },
+    prelude::*,
+    reflect::{ReflectRef, TypeRegistry},
+    tasks::{block_on, futures_lite::StreamExt},
+    utils::{get_short_name, HashMap, HashSet},
+};
+use egui_dock::egui;
+use std::{
+    any::Any,
+    path::{Path, PathBuf},
+};
+
+/// File browser over the default asset source, shown in the Files tab
+///
+/// Directories are read when they're first opened, and cached until refreshed.
+#[derive(Resource, Default)]
+pub(crate) struct AssetBrowser {
+    directories: HashMap<PathBuf, Result<Vec<BrowserEntry>, String>>,
+    /// Asset type names by file extension, `None` if no loader handles the extension
+    loader_types: HashMap<String, Option<&'static str>>,
+    /// Handles of files loaded from the browser, so they aren't dropped right away
+    loaded: HashMap<PathBuf, Handle<LoadedUntypedAsset>>,
+    /// Assets referenced from components, resources or other assets, as of the last scan
+    referenced: Option<HashSet<UntypedAssetId>>,
+    only_unused: bool,
+}
+
+#[derive(Clone)]
+pub(crate) struct BrowserEntry {
+    pub(crate) path: PathBuf,
+    pub(crate) is_directory: bool,
+}
+
+pub(crate) fn asset_browser_ui(
+    world: &mut World,
+    ui: &mut egui::Ui,
+    type_registry: &TypeRegistry,
+    selection: &mut InspectorSelection,
+) {
+    if !world.contains_resource::<AssetServer>() {
+        ui.label("Add `AssetPlugin` to browse assets here");
+        return;
+    }
+
+    world.resource_scope::<AssetBrowser, _>(|world, mut browser| {
+        let asset_server = world.resource::<AssetServer>();
+
+        ui.horizontal(|ui| {
+            if ui.button("Refresh").clicked() {
+                browser.directories.clear();
+            }
+            if ui
+                .button("Find unused")
+                .on_hover_text("Look for loaded assets that nothing references")
+                .clicked()
+            {
+                browser.referenced = Some(referenced_assets(world, type_registry));
+            }
+            ui.add_enabled(
+                browser.referenced.is_some(),
+                egui::Checkbox::new(&mut browser.only_unused, "Only unused"),
+            );
+
+            if asset_server.watching_for_changes() {
+                ui.colored_label(egui::Color32::GREEN, "Hot reloading");
+            } else {
+                ui.weak("Hot reloading off").on_hover_text(
+                    "Enable bevy's `file_watcher` feature to reload assets when they change",
+                );
+            }
+        });
+
+        ui.separator();
+
+        egui::ScrollArea::vertical()
+            .auto_shrink(false)
+            .show(ui, |ui| {
+                browser.directory_ui(ui, world, type_registry, Path::new(""), selection);
+            });
+    });
+}
+
+impl AssetBrowser {
+    fn directory_ui(
+        &mut self,
+        ui: &mut egui::Ui,
+        world: &World,
+        type_registry: &TypeRegistry,
+        path: &Path,
+        selection: &mut InspectorSelection,
+    ) {
+        let asset_server = world.resource::<AssetServer>();
+        let entries = self
+            .directories
+            .entry(path.to_owned())
+            .or_insert_with(|| read_directory(asset_server, path))
+            .clone();
+
+        let entries = match entries {
+            Ok(entries) => entries,
+            Err(error) => {
+                ui.colored_label(egui::Color32::RED, error);
+                return;
+            }
+        };
+
+        for entry in entries {
+            if entry.is_directory {
+                egui::CollapsingHeader::new(file_name(&entry.path))
+                    .id_source(&entry.path)
+                    .show(ui, |ui| {
+                        self.directory_ui(ui, world, type_registry, &entry.path, selection);
+                    });
+            } else {
+                self.file_ui(ui, world, type_registry, &entry.path, selection);
+            }
+        }
+    }
+
+    fn file_ui(
+        &mut self,
+        ui: &mut egui::Ui,
+        world: &World,
+        type_registry: &TypeRegistry,
+        path: &Path,
+        selection: &mut InspectorSelection,
+    ) {
+        let asset_server = world.resource::<AssetServer>();
+        let asset_path = AssetPath::from_path(path).into_owned();
+        let ids = asset_server.get_path_ids(&asset_path);
+        let asset_type = self.loader_type(asset_server, path);
+
+        let unused = !ids.is_empty()
+            && self
+                .referenced
+                .as_ref()
+                .is_some_and(|referenced| !ids.iter().any(|id| referenced.contains(id)));
+        if self.only_unused && self.referenced.is_some() && !unused {
+            return;
+        }
+
+        ui.horizontal(|ui| {
+            match ids.first() {
+                Some(&id) => assets::load_state_ui(world, ui, id),
+                None => {
+                    ui.allocate_exact_size(egui::Vec2::splat(8.), egui::Sense::hover());
+                }
+            }
+
+            let selected = match *selection {
+                InspectorSelection::Asset(_, _, selected_id) => ids.contains(&selected_id),
+                _ => false,
+            };
+            let label = |ui: &mut egui::Ui| ui.selectable_label(selected, file_name(path));
+            let response = if asset_type == Some(std::any::type_name::<Image>()) {
+                let id = egui::Id::new(("asset_drag", path));
+                ui.dnd_drag_source(id, AssetDrag::Path(asset_path.clone()), label)
+                    .inner
+            } else {
+                label(ui)
+            };
+
+            let hover_text = if ids.is_empty() {
+                "Click to load"
+            } else {
+                "Click to inspect"
+            };
+            if response.on_hover_text(hover_text).clicked() {
+                match ids.first() {
+                    Some(&id) => {
+                        let name = type_registry
+                            .get(id.type_id())
+                            .map(|registration| {
+                                registration.type_info().type_path_table().short_path()
+                            })
+                            .unwrap_or_default();
+                        *selection = InspectorSelection::Asset(id.type_id(), name.to_string(), id);
+                    }
+                    None => {
+                        let handle = asset_server.load_untyped(asset_path.clone());
+                        self.loaded.insert(path.to_owned(), handle);
+                    }
+                }
+            }
+
+            ui.weak(
+                asset_type
+                    .map(get_short_name)
+                    .unwrap_or_else(|| "-".to_string()),
+            );
+
+            if let Some(count) = ids
+                .first()
+                .and_then(|&id| assets::strong_handle_count(world, id))
+            {
+                ui.weak(format!("×{count}"));
+            }
+
+            if unused {
+                ui.colored_label(egui::Color32::YELLOW, "unused")
+                    .on_hover_text("Not referenced by any component, resource or other asset");
+            }
+        });
+    }
+
+    /// Name of the asset type loaded for the file's extension, if any loader handles it
+    fn loader_type(&mut self, asset_server: &AssetServer, path: &Path) -> Option<&'static str> {
+        let extension = path.extension()?.to_str()?.to_lowercase();
+        *self
+            .loader_types
+            .entry(extension)
+            .or_insert_with_key(|extension| {
+                block_on(asset_server.get_asset_loader_with_extension(extension))
+                    .ok()
+                    .map(|loader| loader.asset_type_name())
+            })
+    }
+}
+
+/// Lists a directory of the default asset source, with directories first
+pub(crate) fn read_directory(
+    asset_server: &AssetServer,
+    path: &Path,
+) -> Result<Vec<BrowserEntry>, String> {
+    let source = asset_server
+        .get_source(AssetSourceId::Default)
+        .map_err(|error| error.to_string())?;
+    let reader = source.reader();
+
+    block_on(async {
+        let mut paths = reader
+            .read_directory(path)
+            .await
+            .map_err(|error| error.to_string())?;
+
+        let mut entries = Vec::new();
+        while let Some(path) = paths.next().await {
+            let is_directory = reader.is_directory(&path).await.unwrap_or(false);
+            entries.push(BrowserEntry { path, is_directory });
+        }
+        entries.sort_by(|a, b| {
+            b.is_directory
+                .cmp(&a.is_directory)
+                .then_with(|| a.path.cmp(&b.path))
+        });
+        Ok(entries)
+    })
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
+
+/// Collects every asset with a handle in a reflected component, resource or asset
+fn referenced_assets(world: &World, type_registry: &TypeRegistry) -> HashSet<UntypedAssetId> {
+    let mut referenced = HashSet::new();
+
+    for entity in world.iter_entities() {
+        for component_id in entity.archetype().components() {
+            let Some(type_id) = world
+                .components()
+                .get_info(component_id)
+                .and_then(|info| info.type_id())
+            else {
+                continue;
+            };
+            let Some(reflect_component) = type_registry.get_type_data::<ReflectComponent>(type_id)
+            else {
+                continue;
+            };
+            if let Some(component) = reflect_component.reflect(entity) {
+                collect_handles(component, type_registry, &mut referenced);
+            }
+        }
+    }
+
+    for registration in type_registry.iter() {
+        if let Some(resource) = registration
+            .data::<ReflectResource>()
+            .and_then(|reflect_resource| reflect_resource.reflect(world))
+        {
+            collect_handles(resource, type_registry, &mut referenced);
+        }
+
+        if let Some(reflect_asset) = registration.data::<ReflectAsset>() {
+            for id in reflect_asset.ids(world) {
+                if let Some(asset) = reflect_asset.get(world, UntypedHandle::Weak(id)) {
+                    collect_handles(asset, type_registry, &mut referenced);
+                }
+            }
+        }
+    }
+
+    referenced
+}
+
+fn collect_handles(
+    value: &dyn Reflect,
+    type_registry: &TypeRegistry,
+    handles: &mut HashSet<UntypedAssetId>,
+) {
+    if let Some(reflect_handle) =
+        type_registry.get_type_data::<ReflectHandle>(Any::type_id(value.as_any()))
+    {
+        if let Some(handle) = reflect_handle.downcast_handle_untyped(value.as_any()) {
+            handles.insert(handle.id());
+        }
+        return;
+    }
+
+    let mut collect = |field: &dyn Reflect| collect_handles(field, type_registry, handles);
+    match value.reflect_ref() {
+        ReflectRef::Struct(value) => value.iter_fields().for_each(&mut collect),
+        ReflectRef::TupleStruct(value) => value.iter_fields().for_each(&mut collect),
+        ReflectRef::Tuple(value) => value.iter_fields().for_each(&mut collect),
+        ReflectRef::List(value) => value.iter().for_each(&mut collect),
+        ReflectRef::Array(value) => value.iter().for_each(&mut collect),
+        ReflectRef::Map(value) => value.iter().for_each(|(_, value)| collect(value)),
+        ReflectRef::Enum(value) => value.iter_fields().for_each(|field| collect(field.value())),
+        ReflectRef::Value(_) => {}
+    }
+}
@@ -0,0 +1,317 @@
+use crate::InspectorSelection;
+use bevy::{
+    asset::{AssetPath, LoadState, ReflectAsset, UntypedAssetId},
+    prelude::*,
+    reflect::TypeRegistry,
+    render::render_resource::{TextureDimension, TextureSampleType},
+    utils::HashSet,
+};
+use bevy_inspector_egui::bevy_egui::EguiUserTextures;
+use egui_dock::egui;
+use std::sync::Arc;
+
+/// Size of the previews shown next to each asset
+const THUMBNAIL_SIZE: f32 = 32.;
+
+/// Size of the previews shown when hovering a thumbnail
+const HOVER_PREVIEW_SIZE: f32 = 256.;
+
+/// An asset dragged from the editor, which spawns a sprite when dropped in the game view
+#[derive(Clone)]
+pub(crate) enum AssetDrag {
+    Image(AssetId<Image>),
+    /// An image file that might not have been loaded yet
+    Path(AssetPath<'static>),
+}
+
+#[derive(Resource, Default)]
+pub(crate) struct AssetsPanel {
+    /// Images registered as egui textures for thumbnails, removed again once the image is gone
+    thumbnails: HashSet<AssetId<Image>>,
+}
+
+pub(crate) fn assets_ui(
+    world: &mut World,
+    ui: &mut egui::Ui,
+    type_registry: &TypeRegistry,
+    selection: &mut InspectorSelection,
+) {
+    let mut assets: Vec<_> = type_registry
+        .iter()
+        .filter_map(|registration| {
+            let reflect_asset = registration.data::<ReflectAsset>()?;
+            Some((
+                registration.type_info().type_path_table().short_path(),
+                registration.type_id(),
+                reflect_asset,
+            ))
+        })
+        .collect();
+    assets.sort_by(|(name_a, ..), (name_b, ..)| name_a.cmp(name_b));
+
+    for (asset_name, asset_type_id, reflect_asset) in assets {
+        let handles: Vec<_> = reflect_asset.ids(world).collect();
+
+        ui.collapsing(format!("{asset_name} ({})", handles.len()), |ui| {
+            for handle in handles {
+                let selected = match *selection {
+                    InspectorSelection::Asset(_, _, selected_id) => selected_id == handle,
+                    _ => false,
+                };
+
+                ui.horizontal(|ui| {
+                    match handle.try_typed::<Image>() {
+                        Ok(image) => {
+                            let id = egui::Id::new(("asset_drag", handle));
+                            ui.dnd_drag_source(id, AssetDrag::Image(image), |ui| {
+                                preview_ui(world, ui, handle);
+                            });
+                        }
+                        Err(_) => preview_ui(world, ui, handle),
+                    }
+                    load_state_ui(world, ui, handle);
+
+                    let path = world
+                        .get_resource::<AssetServer>()
+                        .and_then(|asset_server| asset_server.get_path(handle))
+                        .map(|path| path.to_string());
+                    let label = path.unwrap_or_else(|| format!("{:?}", handle));
+
+                    if ui
+                        .selectable_label(selected, label)
+                        .on_hover_text(format!("{:?}", handle))
+                        .clicked()
+                    {
+                        *selection = InspectorSelection::Asset(
+                            asset_type_id,
+                            asset_name.to_string(),
+                            handle,
+                        );
+                    }
+
+                    if let Some(count) = strong_handle_count(world, handle) {
+                        ui.weak(format!("×{count}"))
+                            .on_hover_text("Strong handles keeping the asset alive");
+                    }
+                });
+            }
+        });
+    }
+
+    remove_stale_thumbnails(world);
+}
+
+/// Spawns a sprite for an image dropped in the game view
+pub(crate) fn spawn_dropped_asset(
+    world: &mut World,
+    drag: &AssetDrag,
+    position: Vec2,
+) -> Option<Entity> {
+    let (texture, path) = match drag {
+        AssetDrag::Image(id) => {
+            let texture = world
+                .resource_mut::<Assets<Image>>()
+                .get_strong_handle(*id)?;
+            let path = world
+                .get_resource::<AssetServer>()
+                .and_then(|asset_server| asset_server.get_path(*id))
+                .map(|path| path.into_owned());
+            (texture, path)
+        }
+        AssetDrag::Path(path) => {
+            let texture = world.get_resource::<AssetServer>()?.load(path.clone());
+            (texture, Some(path.clone()))
+        }
+    };
+
+    let name = path
+        .as_ref()
+        .and_then(|path| path.path().file_stem())
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "Sprite".to_string());
+
+    let entity = world
+        .spawn((
+            Name::new(name),
+            SpriteBundle {
+                texture,
+                transform: Transform::from_translation(position.extend(0.)),
+                ..default()
+            },
+        ))
+        .id();
+    Some(entity)
+}
+
+fn preview_ui(world: &mut World, ui: &mut egui::Ui, id: UntypedAssetId) {
+    if let Ok(id) = id.try_typed::<Image>() {
+        image_preview(world, ui, id, egui::Color32::WHITE);
+    } else if let Ok(id) = id.try_typed::<TextureAtlasLayout>() {
+        match world
+            .get_resource::<Assets<TextureAtlasLayout>>()
+            .and_then(|layouts| layouts.get(id))
+        {
+            Some(layout) => atlas_preview(ui, layout),
+            None => placeholder(ui),
+        }
+    } else if let Ok(id) = id.try_typed::<ColorMaterial>() {
+        let Some(material) = world
+            .get_resource::<Assets<ColorMaterial>>()
+            .and_then(|materials| materials.get(id))
+        else {
+            placeholder(ui);
+            return;
+        };
+        let (color, texture) = (material.color, material.texture.as_ref().map(Handle::id));
+        match texture {
+            Some(texture) => image_preview(world, ui, texture, egui_color(color)),
+            None => {
+                let (rect, response) =
+                    ui.allocate_exact_size(egui::Vec2::splat(THUMBNAIL_SIZE), egui::Sense::hover());
+                ui.painter().rect_filled(rect, 2., egui_color(color));
+                response.on_hover_text(format!("{color:?}"));
+            }
+        }
+    } else {
+        placeholder(ui);
+    }
+}
+
+fn placeholder(ui: &mut egui::Ui) {
+    ui.allocate_exact_size(egui::Vec2::splat(THUMBNAIL_SIZE), egui::Sense::hover());
+}
+
+/// Registers the image as an egui texture, returns it and the image's size if egui can show it
+pub(crate) fn egui_texture(
+    world: &mut World,
+    id: AssetId<Image>,
+) -> Option<(egui::TextureId, Vec2)> {
+    let size = match world.resource::<Assets<Image>>().get(id) {
+        Some(image) if can_preview(image) => image.size_f32(),
+        _ => return None,
+    };
+
+    let handle = Handle::Weak(id);
+    let mut user_textures = world.resource_mut::<EguiUserTextures>();
+    let texture_id = match user_textures.image_id(&handle) {
+        Some(texture_id) => texture_id,
+        None => user_textures.add_image(handle),
+    };
+    world.resource_mut::<AssetsPanel>().thumbnails.insert(id);
+    Some((texture_id, size))
+}
+
+/// Shows the image scaled down to a thumbnail, with a larger version on hover
+fn image_preview(world: &mut World, ui: &mut egui::Ui, id: AssetId<Image>, tint: egui::Color32) {
+    let Some((texture_id, size)) = egui_texture(world, id) else {
+        placeholder(ui);
+        return;
+    };
+
+    ui.add(egui::Image::new((texture_id, fit(size, THUMBNAIL_SIZE))).tint(tint))
+        .on_hover_ui(|ui| {
+            ui.add(egui::Image::new((texture_id, fit(size, HOVER_PREVIEW_SIZE))).tint(tint));
+            ui.label(format!("{} × {}", size.x, size.y));
+        });
+}
+
+/// Whether egui can sample the image, other kinds of textures would fail to bind
+fn can_preview(image: &Image) -> bool {
+    let descriptor = &image.texture_descriptor;
+    descriptor.dimension == TextureDimension::D2
+        && descriptor.size.depth_or_array_layers == 1
+        && image.texture_view_descriptor.is_none()
+        && descriptor.format.sample_type(None, None)
+            == Some(TextureSampleType::Float { filterable: true })
+}
+
+/// Draws the outlines of the atlas' sections, with a larger version on hover
+fn atlas_preview(ui: &mut egui::Ui, layout: &TextureAtlasLayout) {
+    let paint = |ui: &mut egui::Ui, max_size: f32| {
+        let size = fit(layout.size.as_vec2(), max_size);
+        let (rect, response) = ui.allocate_exact_size(size, egui::Sense::hover());
+        let painter = ui.painter();
+        painter.rect_filled(rect, 0., ui.visuals().extreme_bg_color);
+
+        let scale = size.x / layout.size.x.max(1) as f32;
+        let stroke = egui::Stroke::new(1., ui.visuals().widgets.active.fg_stroke.color);
+        for section in &layout.textures {
+            let min = rect.min + egui::vec2(section.min.x as f32, section.min.y as f32) * scale;
+            let max = rect.min + egui::vec2(section.max.x as f32, section.max.y as f32) * scale;
+            painter.rect_stroke(egui::Rect::from_min_max(min, max), 0., stroke);
+        }
+        response
+    };
+
+    paint(ui, THUMBNAIL_SIZE).on_hover_ui(|ui| {
+        paint(ui, HOVER_PREVIEW_SIZE);
+        ui.label(format!(
+            "{} sections in {} × {}",
+            layout.textures.len(),
+            layout.size.x,
+            layout.size.y
+        ));
+    });
+}
+
+pub(crate) fn load_state_ui(world: &World, ui: &mut egui::Ui, id: UntypedAssetId) {
+    // assets added directly to `Assets` are not managed by the asset server
+    let Some(load_state) = world
+        .get_resource::<AssetServer>()
+        .and_then(|asset_server| asset_server.get_load_state(id))
+    else {
+        return;
+    };
+
+    let (color, text) = match load_state {
+        LoadState::NotLoaded => (egui::Color32::GRAY, "Not loaded".to_string()),
+        LoadState::Loading => (egui::Color32::YELLOW, "Loading".to_string()),
+        LoadState::Loaded => (egui::Color32::GREEN, "Loaded".to_string()),
+        LoadState::Failed(error) => (egui::Color32::RED, format!("Failed: {error}")),
+    };
+
+    let (rect, response) = ui.allocate_exact_size(egui::Vec2::splat(8.), egui::Sense::hover());
+    ui.painter().circle_filled(rect.center(), 4., color);
+    response.on_hover_text(text);
+}
+
+/// Counts the strong handles of assets managed by the asset server
+pub(crate) fn strong_handle_count(world: &World, id: UntypedAssetId) -> Option<usize> {
+    let handle = world
+        .get_resource::<AssetServer>()?
+        .get_id_handle_untyped(id)?;
+    match &handle {
+        // don't count the handle we just got
+        UntypedHandle::Strong(handle) => Some(Arc::strong_count(handle) - 1),
+        UntypedHandle::Weak(_) => None,
+    }
+}
+
+fn remove_stale_thumbnails(world: &mut World) {
+    let images = world.resource::<Assets<Image>>();
+    let stale: Vec<_> = world
+        .resource::<AssetsPanel>()
+        .thumbnails
+        .iter()
+        .filter(|&&id| !images.contains(id))
+        .copied()
+        .collect();
+
+    for id in stale {
+        world
+            .resource_mut::<EguiUserTextures>()
+            .remove_image(&Handle::Weak(id));
+        world.resource_mut::<AssetsPanel>().thumbnails.remove(&id);
+    }
+}
+
+/// Scales `size` to fit within a square of `max_size`, keeping the aspect ratio
+pub(crate) fn fit(size: Vec2, max_size: f32) -> egui::Vec2 {
+    let scale = max_size / size.max_element().max(1.);
+    egui::vec2(size.x, size.y) * scale
+}
+
+fn egui_color(color: Color) -> egui::Color32 {
+    let [r, g, b, a] = color.to_srgba().to_u8_array();
+    egui::Color32::from_rgba_unmultiplied(r, g, b, a)
+}
@@ -0,0 +1,234 @@
+use crate::assets::egui_texture;
+use bevy::prelude::*;
+use egui_dock::egui;
+
+/// Largest width the atlas image is shown at in the inspector, in points
+const MAX_PREVIEW_WIDTH: f32 = 400.;
+
+/// Settings of the "Create layout from grid" window
+#[derive(Resource)]
+pub(crate) struct AtlasGrid {
+    /// The entity the layout is created for while the window is open
+    entity: Option<Entity>,
+    tile_size: UVec2,
+    columns: u32,
+    rows: u32,
+    padding: UVec2,
+    offset: UVec2,
+}
+
+impl Default for AtlasGrid {
+    fn default() -> Self {
+        Self {
+            entity: None,
+            tile_size: UVec2::splat(16),
+            columns: 1,
+            rows: 1,
+            padding: UVec2::ZERO,
+            offset: UVec2::ZERO,
+        }
+    }
+}
+
+impl AtlasGrid {
+    /// Fits as many tiles as possible into an image of `size`
+    fn fit(&mut self, size: UVec2) {
+        let step = self.tile_size + self.padding;
+        let available = (size.saturating_sub(self.offset) + self.padding) / step.max(UVec2::ONE);
+        self.columns = available.x.max(1);
+        self.rows = available.y.max(1);
+    }
+}
+
+/// Shows the frames of the entity's texture atlas on its image, clicking a frame selects it
+///
+/// Sprites without an atlas can get one from the "Create layout from grid" window.
+pub(crate) fn texture_atlas_ui(world: &mut World, ui: &mut egui::Ui, entity: Entity) {
+    let Some(image) = world.get::<Handle<Image>>(entity).map(Handle::id) else {
+        return;
+    };
+    if world.get::<Sprite>(entity).is_none() {
+        return;
+    }
+    let atlas = world.get::<TextureAtlas>(entity).cloned();
+
+    egui::CollapsingHeader::new("Texture atlas")
+        .default_open(true)
+        .show(ui, |ui| {
+            if let Some(atlas) = atlas {
+                frame_picker_ui(world, ui, entity, image, atlas);
+            }
+
+            if ui.button("Create layout from grid").clicked() {
+                let size = world
+                    .resource::<Assets<Image>>()
+                    .get(image)
+                    .map(Image::size);
+                let mut grid = world.resource_mut::<AtlasGrid>();
+                grid.entity = Some(entity);
+                if let Some(size) = size {
+                    grid.fit(size);
+                }
+            }
+        });
+
+    if world.resource::<AtlasGrid>().entity == Some(entity) {
+        grid_window_ui(world, ui, entity, image);
+    }
+}
+
+fn frame_picker_ui(
+    world: &mut World,
+    ui: &mut egui::Ui,
+    entity: Entity,
+    image: AssetId<Image>,
+    atlas: TextureAtlas,
+) {
+    let Some(frames) = world
+        .resource::<Assets<TextureAtlasLayout>>()
+        .get(&atlas.layout)
+        .map(|layout| layout.textures.clone())
+    else {
+        ui.weak("The atlas layout isn't loaded");
+        return;
+    };
+
+    let mut index = atlas.index;
+    ui.horizontal(|ui| {
+        ui.label("Frame");
+        ui.add(egui::DragValue::new(&mut index).range(0..=frames.len().saturating_sub(1)));
+        ui.weak(format!("of {}", frames.len()));
+    });
+
+    if let Some((texture_id, size)) = egui_texture(world, image) {
+        let width = ui.available_width().min(MAX_PREVIEW_WIDTH);
+        let scale = width / size.x.max(1.);
+        let (rect, response) =
+            ui.allocate_exact_size(egui::vec2(size.x, size.y) * scale, egui::Sense::click());
+        let to_screen = |frame: URect| {
+            egui::Rect::from_min_max(
+                rect.min + egui::vec2(frame.min.x as f32, frame.min.y as f32) * scale,
+                rect.min + egui::vec2(frame.max.x as f32, frame.max.y as f32) * scale,
+            )
+        };
+
+        let painter = ui.painter_at(rect);
+        let visuals = ui.visuals();
+        painter.rect_filled(rect, 0., visuals.extreme_bg_color);
+        painter.image(
+            texture_id,
+            rect,
+            egui::Rect::from_min_max(egui::pos2(0., 0.), egui::pos2(1., 1.)),
+            egui::Color32::WHITE,
+        );
+
+        let hovered = response.hover_pos().and_then(|pos| {
+            frames
+                .iter()
+                .position(|&frame| to_screen(frame).contains(pos))
+        });
+        for (frame_index, &frame) in frames.iter().enumerate() {
+            let frame_rect = to_screen(frame);
+            if Some(frame_index) == hovered {
+                painter.rect_filled(
+                    frame_rect,
+                    0.,
+                    visuals.selection.bg_fill.gamma_multiply(0.3),
+                );
+            }
+            let stroke = if frame_index == index {
+                egui::Stroke::new(2., visuals.selection.stroke.color)
+            } else {
+                egui::Stroke::new(1., visuals.weak_text_color())
+            };
+            painter.rect_stroke(frame_rect, 0., stroke);
+        }
+
+        if let (Some(hovered), true) = (hovered, response.clicked()) {
+            index = hovered;
+        }
+        response.on_hover_text("Click a frame to show it");
+    }
+
+    if index != atlas.index {
+        if let Some(mut atlas) = world.get_mut::<TextureAtlas>(entity) {
+            atlas.index = index;
+        }
+    }
+}
+
+/// Window for generating a [`TextureAtlasLayout`] from a grid of tiles on the image
+fn grid_window_ui(world: &mut World, ui: &mut egui::Ui, entity: Entity, image: AssetId<Image>) {
+    let size = world
+        .resource::<Assets<Image>>()
+        .get(image)
+        .map(Image::size);
+
+    let mut open = true;
+    let mut create = false;
+    world.resource_scope::<AtlasGrid, _>(|_, mut grid| {
+        egui::Window::new("Create layout from grid")
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(false)
+            .show(ui.ctx(), |ui| {
+                egui::Grid::new("atlas_grid").show(ui, |ui| {
+                    ui.label("Tile size");
+                    ui.add(egui::DragValue::new(&mut grid.tile_size.x).range(1..=u32::MAX));
+                    ui.add(egui::DragValue::new(&mut grid.tile_size.y).range(1..=u32::MAX));
+                    ui.end_row();
+
+                    ui.label("Columns and rows");
+                    ui.add(egui::DragValue::new(&mut grid.columns).range(1..=u32::MAX));
+                    ui.add(egui::DragValue::new(&mut grid.rows).range(1..=u32::MAX));
+                    ui.end_row();
+
+                    ui.label("Padding");
+                    ui.add(egui::DragValue::new(&mut grid.padding.x));
+                    ui.add(egui::DragValue::new(&mut grid.padding.y));
+                    ui.end_row();
+
+                    ui.label("Offset");
+                    ui.add(egui::DragValue::new(&mut grid.offset.x));
+                    ui.add(egui::DragValue::new(&mut grid.offset.y));
+                    ui.end_row();
+                });
+
+                ui.horizontal(|ui| {
+                    if let Some(size) = size {
+                        if ui
+                            .button("Fit to image")
+                            .on_hover_text(format!(
+                                "As many tiles as fit in the {} × {} image",
+                                size.x, size.y
+                            ))
+                            .clicked()
+                        {
+                            grid.fit(size);
+                        }
+                    }
+                    create = ui.button("Create").clicked();
+                });
+            });
+    });
+
+    if create {
+        let grid = world.resource::<AtlasGrid>();
+        let layout = TextureAtlasLayout::from_grid(
+            grid.tile_size,
+            grid.columns,
+            grid.rows,
+            Some(grid.padding),
+            Some(grid.offset),
+        );
+        let layout = world
+            .resource_mut::<Assets<TextureAtlasLayout>>()
+            .add(layout);
+        if let Some(mut entity) = world.get_entity_mut(entity) {
+            entity.insert(TextureAtlas { layout, index: 0 });
+        }
+    }
+    if create || !open {
+        world.resource_mut::<AtlasGrid>().entity = None;
+    }
+}
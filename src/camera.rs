@@ -0,0 +1,563 @@
+use crate::{pixels_per_unit, viewport_to_world, Ed2dCamera, UiState};
+use bevy::{asset::io::file::FileAssetReader, prelude::*, render::view::RenderLayers, scene::ron};
+use egui_dock::egui;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+/// Where bookmarks are saved, relative to the project's root
+const BOOKMARKS_FILE: &str = ".ed2d/camera_bookmarks.ron";
+
+/// How many camera jumps can be navigated back to
+const MAX_HISTORY: usize = 50;
+
+/// Points scrolled per zoom step, about one notch of a mouse wheel
+const SCROLL_STEP: f32 = 50.;
+
+/// How much of the view height the keyboard pans per second
+const KEYBOARD_PAN_SPEED: f32 = 1.;
+
+/// How much the keyboard zooms per second, `2.` doubles or halves the zoom
+const KEYBOARD_ZOOM_SPEED: f32 = 2.;
+
+/// Speed multiplier while holding shift
+const FAST_MULTIPLIER: f32 = 3.;
+
+const PAN_BUTTONS: [egui::PointerButton; 2] =
+    [egui::PointerButton::Middle, egui::PointerButton::Secondary];
+
+const BOOKMARK_KEYS: [KeyCode; 9] = [
+    KeyCode::Digit1,
+    KeyCode::Digit2,
+    KeyCode::Digit3,
+    KeyCode::Digit4,
+    KeyCode::Digit5,
+    KeyCode::Digit6,
+    KeyCode::Digit7,
+    KeyCode::Digit8,
+    KeyCode::Digit9,
+];
+
+/// Zoom and panning limits of the editor camera
+#[derive(Resource, Reflect, Clone)]
+#[reflect(Resource)]
+pub struct Ed2dCameraSettings {
+    /// Smallest projection scale, i.e. how far the camera can zoom in
+    pub min_scale: f32,
+    /// Largest projection scale, i.e. how far the camera can zoom out
+    pub max_scale: f32,
+    /// Area the center of the camera is kept within, unbounded if `None`
+    pub bounds: Option<Rect>,
+    /// How much each scroll step zooms, `0.1` zooms by 10%
+    pub zoom_speed: f32,
+    /// Whether zooming keeps the point under the cursor in place, instead of the center of the view
+    pub zoom_to_cursor: bool,
+    /// Render layers of the game shown in the game views, can also be toggled from the Layers tab
+    pub render_layers: RenderLayers,
+}
+
+impl Default for Ed2dCameraSettings {
+    fn default() -> Self {
+        Self {
+            min_scale: 0.01,
+            max_scale: 100.,
+            bounds: None,
+            zoom_speed: 0.1,
+            zoom_to_cursor: true,
+            render_layers: RenderLayers::default(),
+        }
+    }
+}
+
+/// Position and zoom of the editor camera
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(from = "[f32; 3]", into = "[f32; 3]")]
+pub(crate) struct CameraView {
+    pub(crate) translation: Vec2,
+    pub(crate) scale: f32,
+}
+
+impl CameraView {
+    pub(crate) fn new(transform: &Transform, projection: &OrthographicProjection) -> Self {
+        Self {
+            translation: transform.translation.xy(),
+            scale: projection.scale,
+        }
+    }
+}
+
+impl From<[f32; 3]> for CameraView {
+    fn from([x, y, scale]: [f32; 3]) -> Self {
+        Self {
+            translation: Vec2::new(x, y),
+            scale,
+        }
+    }
+}
+
+impl From<CameraView> for [f32; 3] {
+    fn from(view: CameraView) -> Self {
+        [view.translation.x, view.translation.y, view.scale]
+    }
+}
+
+enum BookmarkAction {
+    Recall(usize),
+    Store(usize),
+}
+
+/// Camera views saved with Ctrl+1..9, shared by all game views
+#[derive(Resource, Default)]
+pub(crate) struct CameraBookmarks {
+    bookmarks: [Option<CameraView>; 9],
+}
+
+/// History of an editor camera's jumps
+#[derive(Component, Default)]
+pub(crate) struct CameraNavigation {
+    back: Vec<CameraView>,
+    forward: Vec<CameraView>,
+    /// The view the camera is animating towards
+    target: Option<CameraView>,
+}
+
+impl CameraNavigation {
+    /// Animates the camera to `to`, remembering `from` so it can be navigated back to
+    pub(crate) fn jump(&mut self, from: CameraView, to: CameraView) {
+        if self.back.len() == MAX_HISTORY {
+            self.back.remove(0);
+        }
+        self.back.push(from);
+        self.forward.clear();
+        self.target = Some(to);
+    }
+
+    /// Animates the camera to `view`, without adding to the history
+    pub(crate) fn move_to(&mut self, view: CameraView) {
+        self.target = Some(view);
+    }
+
+    fn go_back(&mut self, current: CameraView) {
+        if let Some(view) = self.back.pop() {
+            self.forward.push(current);
+            self.target = Some(view);
+        }
+    }
+
+    fn go_forward(&mut self, current: CameraView) {
+        if let Some(view) = self.forward.pop() {
+            self.back.push(current);
+            self.target = Some(view);
+        }
+    }
+}
+
+/// The current view of an editor camera
+pub(crate) fn camera_view(world: &World, camera: Entity) -> Option<CameraView> {
+    let entity = world.get_entity(camera)?;
+    Some(CameraView::new(
+        entity.get::<Transform>()?,
+        entity.get::<OrthographicProjection>()?,
+    ))
+}
+
+fn bookmarks_path() -> PathBuf {
+    FileAssetReader::get_base_path().join(BOOKMARKS_FILE)
+}
+
+pub(crate) fn load_bookmarks(mut bookmarks: ResMut<CameraBookmarks>) {
+    let Ok(contents) = std::fs::read_to_string(bookmarks_path()) else {
+        return;
+    };
+    match ron::from_str(&contents) {
+        Ok(loaded) => bookmarks.bookmarks = loaded,
+        Err(error) => warn!("Failed to read camera bookmarks: {error}"),
+    }
+}
+
+fn save_bookmarks(bookmarks: &[Option<CameraView>; 9]) {
+    let path = bookmarks_path();
+    let result = ron::ser::to_string_pretty(bookmarks, default())
+        .map_err(|error| error.to_string())
+        .and_then(|contents| {
+            if let Some(directory) = path.parent() {
+                std::fs::create_dir_all(directory).map_err(|error| error.to_string())?;
+            }
+            std::fs::write(&path, contents).map_err(|error| error.to_string())
+        });
+    if let Err(error) = result {
+        warn!("Failed to save camera bookmarks to {path:?}: {error}");
+    }
+}
+
+/// Ctrl+1..9 stores a bookmark and 1..9 recalls it, Alt+Left/Right or the mouse's back and forward
+/// buttons navigate the history of the hovered game view's camera
+pub(crate) fn camera_navigation_keys(
+    keys: Res<ButtonInput<KeyCode>>,
+    mouse_buttons: Res<ButtonInput<MouseButton>>,
+    ui_state: Res<UiState>,
+    mut cameras: Query<(&Transform, &OrthographicProjection, &mut CameraNavigation)>,
+    mut bookmarks: ResMut<CameraBookmarks>,
+) {
+    let Some(viewport) = ui_state.hovered_viewport() else {
+        return;
+    };
+    let Ok((transform, projection, mut navigation)) = cameras.get_mut(viewport.camera) else {
+        return;
+    };
+    let current = CameraView::new(transform, projection);
+
+    let ctrl = keys.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight]);
+    let alt = keys.any_pressed([KeyCode::AltLeft, KeyCode::AltRight]);
+
+    for (slot, key) in BOOKMARK_KEYS.into_iter().enumerate() {
+        if !keys.just_pressed(key) {
+            continue;
+        }
+        if ctrl {
+            bookmarks.bookmarks[slot] = Some(current);
+            save_bookmarks(&bookmarks.bookmarks);
+        } else if let Some(view) = bookmarks.bookmarks[slot] {
+            navigation.jump(current, view);
+        }
+    }
+
+    if (alt && keys.just_pressed(KeyCode::ArrowLeft))
+        || mouse_buttons.just_pressed(MouseButton::Back)
+    {
+        navigation.go_back(current);
+    }
+    if (alt && keys.just_pressed(KeyCode::ArrowRight))
+        || mouse_buttons.just_pressed(MouseButton::Forward)
+    {
+        navigation.go_forward(current);
+    }
+}
+
+/// Smoothly moves the cameras towards the targets of their last jumps
+pub(crate) fn animate_camera(
+    mut cameras: Query<(
+        &mut Transform,
+        &mut OrthographicProjection,
+        &mut CameraNavigation,
+    )>,
+    time: Res<Time<Real>>,
+) {
+    for (mut transform, mut projection, mut navigation) in &mut cameras {
+        let Some(target) = navigation.target else {
+            continue;
+        };
+        let view_height = projection.area.height();
+        let snap_distance = view_height * 0.001;
+
+        if Vec2::distance(target.translation, transform.translation.xy()) < snap_distance
+            && (target.scale - projection.scale).abs() < target.scale * 0.001
+        {
+            // snap the final distance
+            transform.translation.x = target.translation.x;
+            transform.translation.y = target.translation.y;
+            projection.scale = target.scale;
+            navigation.target = None;
+        } else {
+            let t = 10. * time.delta_seconds();
+            let new_pos = transform.translation.xy().lerp(target.translation, t);
+
+            transform.translation.x = new_pos.x;
+            transform.translation.y = new_pos.y;
+            projection.scale = projection.scale.lerp(target.scale, t);
+        }
+    }
+}
+
+pub(crate) fn navigation_toolbar_ui(world: &mut World, ui: &mut egui::Ui, camera: Entity) {
+    let Some(current) = camera_view(world, camera) else {
+        return;
+    };
+    let bookmarks = world.resource::<CameraBookmarks>().bookmarks;
+    let Some(mut navigation) = world.get_mut::<CameraNavigation>(camera) else {
+        return;
+    };
+    let can_go_back = !navigation.back.is_empty();
+    let can_go_forward = !navigation.forward.is_empty();
+
+    if ui
+        .add_enabled(can_go_back, egui::Button::new("◀"))
+        .on_hover_text("Back (Alt+Left)")
+        .clicked()
+    {
+        navigation.go_back(current);
+    }
+    if ui
+        .add_enabled(can_go_forward, egui::Button::new("▶"))
+        .on_hover_text("Forward (Alt+Right)")
+        .clicked()
+    {
+        navigation.go_forward(current);
+    }
+
+    let mut action = None;
+    ui.menu_button("Bookmarks", |ui| {
+        for (slot, bookmark) in bookmarks.iter().enumerate() {
+            let key = slot + 1;
+            ui.horizontal(|ui| {
+                let text = match bookmark {
+                    Some(view) => format!(
+                        "{key}: ({:.0}, {:.0}) ×{:.2}",
+                        view.translation.x, view.translation.y, view.scale
+                    ),
+                    None => format!("{key}: empty"),
+                };
+                if ui
+                    .add_enabled(bookmark.is_some(), egui::Button::new(text))
+                    .on_hover_text(format!("Go to the bookmark ({key})"))
+                    .clicked()
+                {
+                    action = Some(BookmarkAction::Recall(slot));
+                    ui.close_menu();
+                }
+                if ui
+                    .small_button("Set")
+                    .on_hover_text(format!("Bookmark the current view (Ctrl+{key})"))
+                    .clicked()
+                {
+                    action = Some(BookmarkAction::Store(slot));
+                }
+            });
+        }
+    });
+
+    match action {
+        Some(BookmarkAction::Recall(slot)) => {
+            if let Some(view) = bookmarks[slot] {
+                navigation.jump(current, view);
+            }
+        }
+        Some(BookmarkAction::Store(slot)) => {
+            let mut bookmarks = world.resource_mut::<CameraBookmarks>();
+            bookmarks.bookmarks[slot] = Some(current);
+            save_bookmarks(&bookmarks.bookmarks);
+        }
+        None => {}
+    }
+}
+
+/// Pans the camera when dragging with the middle or right mouse button, and zooms when scrolling
+pub(crate) fn camera_controls_ui(
+    world: &mut World,
+    ui: &egui::Ui,
+    response: &egui::Response,
+    viewport_rect: egui::Rect,
+    camera: Entity,
+) {
+    let pan = if PAN_BUTTONS
+        .into_iter()
+        .any(|button| response.dragged_by(button))
+    {
+        response.drag_delta()
+    } else {
+        egui::Vec2::ZERO
+    };
+    let scroll = if response.hovered() {
+        ui.input(|input| input.raw_scroll_delta.y)
+    } else {
+        0.
+    };
+    if pan == egui::Vec2::ZERO && scroll == 0. {
+        return;
+    }
+
+    let cursor = response
+        .hover_pos()
+        .and_then(|pos| viewport_to_world(world, camera, viewport_rect, pos));
+    let settings = world.resource::<Ed2dCameraSettings>().clone();
+
+    let Ok((mut transform, mut projection, mut navigation)) = world
+        .query::<(
+            &mut Transform,
+            &mut OrthographicProjection,
+            &mut CameraNavigation,
+        )>()
+        .get_mut(world, camera)
+    else {
+        return;
+    };
+    // manual movement cancels jumps
+    navigation.target = None;
+
+    let world_per_point = projection.area.height() / viewport_rect.height();
+    transform.translation.x -= pan.x * world_per_point;
+    transform.translation.y += pan.y * world_per_point;
+
+    if scroll != 0. {
+        let old_scale = projection.scale;
+        let new_scale = (old_scale * (1. + settings.zoom_speed).powf(-scroll / SCROLL_STEP))
+            .clamp(settings.min_scale, settings.max_scale);
+        projection.scale = new_scale;
+
+        if let Some(cursor) = cursor.filter(|_| settings.zoom_to_cursor) {
+            let center = cursor + (transform.translation.xy() - cursor) * new_scale / old_scale;
+            transform.translation.x = center.x;
+            transform.translation.y = center.y;
+        }
+    }
+}
+
+/// Pans the hovered game view with WASD or the arrow keys, and zooms with +/-, faster while
+/// holding shift
+pub(crate) fn keyboard_camera_controls(
+    keys: Res<ButtonInput<KeyCode>>,
+    ui_state: Res<UiState>,
+    mut cameras: Query<(
+        &mut Transform,
+        &mut OrthographicProjection,
+        &mut CameraNavigation,
+    )>,
+    time: Res<Time<Real>>,
+) {
+    // leave ctrl and alt combinations, such as bookmarks and history, to other shortcuts
+    let modified = keys.any_pressed([
+        KeyCode::ControlLeft,
+        KeyCode::ControlRight,
+        KeyCode::AltLeft,
+        KeyCode::AltRight,
+    ]);
+    if modified {
+        return;
+    }
+    let Some(viewport) = ui_state.hovered_viewport() else {
+        return;
+    };
+
+    let axis = |negative: [KeyCode; 2], positive: [KeyCode; 2]| {
+        keys.any_pressed(positive) as i8 as f32 - keys.any_pressed(negative) as i8 as f32
+    };
+    let direction = Vec2::new(
+        axis(
+            [KeyCode::KeyA, KeyCode::ArrowLeft],
+            [KeyCode::KeyD, KeyCode::ArrowRight],
+        ),
+        axis(
+            [KeyCode::KeyS, KeyCode::ArrowDown],
+            [KeyCode::KeyW, KeyCode::ArrowUp],
+        ),
+    );
+    let zoom = axis(
+        [KeyCode::Equal, KeyCode::NumpadAdd],
+        [KeyCode::Minus, KeyCode::NumpadSubtract],
+    );
+    if direction == Vec2::ZERO && zoom == 0. {
+        return;
+    }
+
+    let fast = keys.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]);
+    let delta_seconds = time.delta_seconds() * if fast { FAST_MULTIPLIER } else { 1. };
+
+    let Ok((mut transform, mut projection, mut navigation)) = cameras.get_mut(viewport.camera)
+    else {
+        return;
+    };
+    // manual movement cancels jumps
+    navigation.target = None;
+
+    let velocity = direction.normalize_or_zero() * projection.area.height() * KEYBOARD_PAN_SPEED;
+    transform.translation += (velocity * delta_seconds).extend(0.);
+    projection.scale *= KEYBOARD_ZOOM_SPEED.powf(zoom * delta_seconds);
+}
+
+/// Keeps the camera within the zoom limits and bounds
+pub(crate) fn constrain_camera(
+    settings: Res<Ed2dCameraSettings>,
+    mut cameras: Query<(&mut Transform, &mut OrthographicProjection), With<Ed2dCamera>>,
+) {
+    for (mut transform, mut projection) in &mut cameras {
+        let scale = projection
+            .scale
+            .clamp(settings.min_scale, settings.max_scale);
+        if scale != projection.scale {
+            projection.scale = scale;
+        }
+
+        if let Some(bounds) = settings.bounds {
+            let center = transform.translation.xy();
+            let clamped = center.clamp(bounds.min, bounds.max);
+            if clamped != center {
+                transform.translation.x = clamped.x;
+                transform.translation.y = clamped.y;
+            }
+        }
+    }
+}
+
+/// Physical pixels per world unit at scale 1
+///
+/// Game views rendering to a window get the window's scale factor from the render target, while
+/// those rendering to an image have it in their projection.
+fn physical_pixels_per_unit(world: &World, camera: Entity) -> f32 {
+    let target_scale_factor = world
+        .get::<Camera>(camera)
+        .and_then(Camera::target_scaling_factor)
+        .unwrap_or(1.);
+    target_scale_factor * pixels_per_unit(world, camera)
+}
+
+/// Zoom percentage, and actions for resetting the view
+pub(crate) fn zoom_toolbar_ui(world: &mut World, ui: &mut egui::Ui, camera: Entity) {
+    let Some(current) = camera_view(world, camera) else {
+        return;
+    };
+    // one world unit per physical pixel, rather than per logical pixel, which shows more of the
+    // world on HiDPI screens
+    let pixel_scale = physical_pixels_per_unit(world, camera);
+
+    let settings = world.resource::<Ed2dCameraSettings>();
+    let mut percent = 100. / current.scale;
+    let zoom_changed = ui
+        .add(
+            egui::DragValue::new(&mut percent)
+                .range(100. / settings.max_scale..=100. / settings.min_scale)
+                .speed(1.)
+                .max_decimals(0)
+                .suffix("%"),
+        )
+        .on_hover_text("Zoom, 100% is one world unit per logical pixel")
+        .changed();
+
+    let mut target = None;
+    if zoom_changed && percent > 0. {
+        target = Some(CameraView {
+            scale: 100. / percent,
+            ..current
+        });
+    }
+    ui.menu_button("View", |ui| {
+        if ui.button("Reset to origin").clicked() {
+            target = Some(CameraView {
+                translation: Vec2::ZERO,
+                scale: 1.,
+            });
+            ui.close_menu();
+        }
+        if ui
+            .button("Zoom to 100% pixels")
+            .on_hover_text("One world unit per physical pixel")
+            .clicked()
+        {
+            target = Some(CameraView {
+                scale: pixel_scale,
+                ..current
+            });
+            ui.close_menu();
+        }
+    });
+
+    let Some(mut navigation) = world.get_mut::<CameraNavigation>(camera) else {
+        return;
+    };
+    if let Some(target) = target {
+        if zoom_changed {
+            // dragging the zoom would otherwise add an entry to the history per step
+            navigation.move_to(target);
+        } else {
+            navigation.jump(current, target);
+        }
+    }
+}
@@ -0,0 +1,257 @@
+use bevy::{
+    log::{
+        tracing_subscriber::{layer::Context, Layer},
+        BoxedLayer,
+    },
+    prelude::*,
+    utils::{
+        tracing::{
+            self,
+            field::{Field, Visit},
+            Level, Subscriber,
+        },
+        HashSet,
+    },
+};
+use egui_dock::egui;
+use std::{
+    collections::{BTreeSet, VecDeque},
+    fmt::Write,
+    sync::{mpsc, Mutex},
+};
+
+/// How many log entries the console keeps before dropping the oldest ones
+const MAX_ENTRIES: usize = 10_000;
+
+/// Captures `tracing` events so they can be shown in the editor's Log tab
+///
+/// Pass it to bevy's `LogPlugin`:
+///
+/// ```ignore
+/// App::new().add_plugins((
+///     DefaultPlugins.set(LogPlugin {
+///         custom_layer: bevy_ed2d::log_layer,
+///         ..default()
+///     }),
+///     Ed2dPlugin::default(),
+/// ));
+/// ```
+pub fn log_layer(app: &mut App) -> Option<BoxedLayer> {
+    let (sender, receiver) = mpsc::channel();
+    app.insert_resource(LogReceiver(Mutex::new(receiver)));
+    Some(Box::new(CaptureLayer { sender }))
+}
+
+struct CaptureLayer {
+    sender: mpsc::Sender<LogEntry>,
+}
+
+impl<S: Subscriber> Layer<S> for CaptureLayer {
+    fn on_event(&self, event: &tracing::Event<'_>, _ctx: Context<'_, S>) {
+        let metadata = event.metadata();
+        let mut message = String::new();
+        event.record(&mut MessageVisitor(&mut message));
+
+        // the receiver is gone if the app has shut down
+        let _ = self.sender.send(LogEntry {
+            level: *metadata.level(),
+            target: metadata.target().to_string(),
+            message,
+        });
+    }
+}
+
+struct MessageVisitor<'a>(&'a mut String);
+
+impl Visit for MessageVisitor<'_> {
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        if field.name() == "message" {
+            let _ = write!(self.0, "{value:?}");
+        } else {
+            let _ = write!(self.0, " {}={value:?}", field.name());
+        }
+    }
+}
+
+struct LogEntry {
+    level: Level,
+    target: String,
+    message: String,
+}
+
+#[derive(Resource)]
+pub(crate) struct LogReceiver(Mutex<mpsc::Receiver<LogEntry>>);
+
+#[derive(Resource)]
+pub(crate) struct Console {
+    entries: VecDeque<LogEntry>,
+    targets: BTreeSet<String>,
+    hidden_targets: HashSet<String>,
+    max_level: Level,
+    search: String,
+}
+
+impl Default for Console {
+    fn default() -> Self {
+        Self {
+            entries: VecDeque::new(),
+            targets: BTreeSet::new(),
+            hidden_targets: HashSet::new(),
+            max_level: Level::TRACE,
+            search: String::new(),
+        }
+    }
+}
+
+pub(crate) fn collect_log_entries(
+    receiver: Option<Res<LogReceiver>>,
+    mut console: ResMut<Console>,
+) {
+    let Some(receiver) = receiver else {
+        return;
+    };
+
+    for entry in receiver.0.lock().unwrap().try_iter() {
+        if !console.targets.contains(&entry.target) {
+            console.targets.insert(entry.target.clone());
+        }
+        if console.entries.len() == MAX_ENTRIES {
+            console.entries.pop_front();
+        }
+        console.entries.push_back(entry);
+    }
+}
+
+/// Returns the entity that was clicked in a log message, if any
+pub(crate) fn console_ui(world: &mut World, ui: &mut egui::Ui) -> Option<Entity> {
+    if !world.contains_resource::<LogReceiver>() {
+        ui.label("Set `bevy_ed2d::log_layer` as the `LogPlugin` custom layer to see logs here");
+        return None;
+    }
+
+    world.resource_mut::<Console>().ui(ui)
+}
+
+impl Console {
+    fn ui(&mut self, ui: &mut egui::Ui) -> Option<Entity> {
+        ui.horizontal(|ui| {
+            egui::ComboBox::from_id_source("log_level")
+                .selected_text(self.max_level.to_string())
+                .show_ui(ui, |ui| {
+                    for level in [
+                        Level::ERROR,
+                        Level::WARN,
+                        Level::INFO,
+                        Level::DEBUG,
+                        Level::TRACE,
+                    ] {
+                        ui.selectable_value(&mut self.max_level, level, level.to_string());
+                    }
+                });
+
+            ui.menu_button("Targets", |ui| {
+                for target in &self.targets {
+                    let mut shown = !self.hidden_targets.contains(target);
+                    if ui.checkbox(&mut shown, target.as_str()).changed() {
+                        if shown {
+                            self.hidden_targets.remove(target);
+                        } else {
+                            self.hidden_targets.insert(target.clone());
+                        }
+                    }
+                }
+            });
+
+            ui.add(egui::TextEdit::singleline(&mut self.search).hint_text("Search"));
+
+            if ui.button("Clear").clicked() {
+                self.entries.clear();
+            }
+        });
+
+        ui.separator();
+
+        let search = self.search.to_lowercase();
+        let entries: Vec<_> = self
+            .entries
+            .iter()
+            .filter(|entry| entry.level <= self.max_level)
+            .filter(|entry| !self.hidden_targets.contains(&entry.target))
+            .filter(|entry| search.is_empty() || entry.message.to_lowercase().contains(&search))
+            .collect();
+
+        let mut clicked = None;
+        let row_height = ui.text_style_height(&egui::TextStyle::Body);
+
+        egui::ScrollArea::both()
+            .auto_shrink(false)
+            .stick_to_bottom(true)
+            .show_rows(ui, row_height, entries.len(), |ui, rows| {
+                for entry in &entries[rows] {
+                    ui.horizontal(|ui| {
+                        ui.spacing_mut().item_spacing.x = 0.;
+                        ui.colored_label(level_color(entry.level), format!("{:>5} ", entry.level));
+                        ui.weak(format!("{}: ", entry.target));
+                        clicked = clicked.or(message_ui(ui, &entry.message));
+                    });
+                }
+            });
+
+        clicked
+    }
+}
+
+fn level_color(level: Level) -> egui::Color32 {
+    match level {
+        Level::ERROR => egui::Color32::RED,
+        Level::WARN => egui::Color32::YELLOW,
+        Level::INFO => egui::Color32::GREEN,
+        Level::DEBUG => egui::Color32::LIGHT_BLUE,
+        _ => egui::Color32::GRAY,
+    }
+}
+
+/// Shows a message on a single line, with entity ids as clickable links
+fn message_ui(ui: &mut egui::Ui, message: &str) -> Option<Entity> {
+    let mut clicked = None;
+    let mut text = String::new();
+
+    for word in message.split_whitespace() {
+        if let Some(entity) = parse_entity(word) {
+            if !text.is_empty() {
+                ui.label(std::mem::take(&mut text));
+            }
+            if ui.link(word).clicked() {
+                clicked = Some(entity);
+            }
+            text.push(' ');
+        } else {
+            text.push_str(word);
+            text.push(' ');
+        }
+    }
+
+    if !text.is_empty() {
+        ui.label(text);
+    }
+
+    clicked
+}
+
+/// Parses entities formatted with their `Display` impl, e.g. `42v1`, or in the `Debug` form
+/// that also has their bits, e.g. `42v1#4294967338`
+fn parse_entity(word: &str) -> Option<Entity> {
+    let word = word.trim_matches(|c: char| !c.is_ascii_alphanumeric());
+    let (word, bits) = match word.split_once('#') {
+        Some((word, bits)) => (word, Some(bits.parse::<u64>().ok()?)),
+        None => (word, None),
+    };
+    let (index, generation) = word.split_once('v')?;
+    let index: u32 = index.parse().ok()?;
+    let generation: u32 = generation.parse().ok()?;
+    let entity = Entity::try_from_bits((u64::from(generation) << 32) | u64::from(index)).ok()?;
+    if bits.is_some_and(|bits| bits != entity.to_bits()) {
+        return None;
+    }
+    Some(entity)
+}
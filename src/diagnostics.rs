@@ -0,0 +1,131 @@
+use bevy::{
+    diagnostic::{
+        Diagnostic, DiagnosticsStore, EntityCountDiagnosticsPlugin, FrameTimeDiagnosticsPlugin,
+    },
+    prelude::*,
+};
+use egui_dock::egui;
+
+/// Built-in diagnostics that were enabled from the Diagnostics tab
+///
+/// The measuring systems are always added, but only run once the diagnostic has been
+/// registered by the editor, so they don't measure twice if the app adds the plugins itself.
+#[derive(Resource, Default)]
+pub(crate) struct EditorDiagnostics {
+    frame_time: bool,
+    entity_count: bool,
+}
+
+pub(crate) fn frame_time_enabled(editor_diagnostics: Res<EditorDiagnostics>) -> bool {
+    editor_diagnostics.frame_time
+}
+
+pub(crate) fn entity_count_enabled(editor_diagnostics: Res<EditorDiagnostics>) -> bool {
+    editor_diagnostics.entity_count
+}
+
+pub(crate) fn diagnostics_ui(world: &mut World, ui: &mut egui::Ui) {
+    let Some(store) = world.get_resource::<DiagnosticsStore>() else {
+        ui.label("Add `DiagnosticsPlugin` to see diagnostics here");
+        return;
+    };
+
+    let has_frame_time = store.get(&FrameTimeDiagnosticsPlugin::FPS).is_some();
+    let has_entity_count = store
+        .get(&EntityCountDiagnosticsPlugin::ENTITY_COUNT)
+        .is_some();
+
+    if !has_frame_time || !has_entity_count {
+        ui.horizontal(|ui| {
+            if !has_frame_time && ui.button("Enable frame time diagnostics").clicked() {
+                enable_frame_time(world);
+            }
+            if !has_entity_count && ui.button("Enable entity count diagnostics").clicked() {
+                enable_entity_count(world);
+            }
+        });
+        ui.separator();
+    }
+
+    let mut store = world.resource_mut::<DiagnosticsStore>();
+    let mut diagnostics: Vec<_> = store.iter_mut().collect();
+    diagnostics.sort_by(|a, b| a.path().as_str().cmp(b.path().as_str()));
+
+    egui::ScrollArea::vertical().show(ui, |ui| {
+        egui::Grid::new("diagnostics")
+            .num_columns(7)
+            .striped(true)
+            .show(ui, |ui| {
+                for header in ["", "Diagnostic", "Value", "Min", "Max", "Avg", "History"] {
+                    ui.strong(header);
+                }
+                ui.end_row();
+
+                for diagnostic in diagnostics {
+                    ui.checkbox(&mut diagnostic.is_enabled, "")
+                        .on_hover_text("Record measurements");
+                    ui.label(diagnostic.path().as_str());
+
+                    let values: Vec<f64> = diagnostic.values().copied().collect();
+                    let min = values.iter().copied().reduce(f64::min);
+                    let max = values.iter().copied().reduce(f64::max);
+                    let suffix = &diagnostic.suffix;
+
+                    ui.label(format_value(diagnostic.smoothed(), suffix));
+                    ui.label(format_value(min, suffix));
+                    ui.label(format_value(max, suffix));
+                    ui.label(format_value(diagnostic.average(), suffix));
+                    history_graph(ui, &values, min.unwrap_or(0.), max.unwrap_or(0.));
+                    ui.end_row();
+                }
+            });
+    });
+}
+
+fn enable_frame_time(world: &mut World) {
+    let mut store = world.resource_mut::<DiagnosticsStore>();
+    store.add(Diagnostic::new(FrameTimeDiagnosticsPlugin::FRAME_TIME).with_suffix("ms"));
+    store.add(Diagnostic::new(FrameTimeDiagnosticsPlugin::FPS));
+    store.add(Diagnostic::new(FrameTimeDiagnosticsPlugin::FRAME_COUNT).with_smoothing_factor(0.0));
+    world.resource_mut::<EditorDiagnostics>().frame_time = true;
+}
+
+fn enable_entity_count(world: &mut World) {
+    let mut store = world.resource_mut::<DiagnosticsStore>();
+    store.add(Diagnostic::new(EntityCountDiagnosticsPlugin::ENTITY_COUNT));
+    world.resource_mut::<EditorDiagnostics>().entity_count = true;
+}
+
+fn format_value(value: Option<f64>, suffix: &str) -> String {
+    match value {
+        Some(value) => format!("{value:.2}{suffix}"),
+        None => "-".to_string(),
+    }
+}
+
+/// Draws the diagnostic history as a line, scaled to fit between min and max
+fn history_graph(ui: &mut egui::Ui, values: &[f64], min: f64, max: f64) {
+    let (rect, _) = ui.allocate_exact_size(egui::vec2(160., 32.), egui::Sense::hover());
+    let painter = ui.painter();
+    painter.rect_filled(rect, 2., ui.visuals().extreme_bg_color);
+
+    if values.len() < 2 {
+        return;
+    }
+
+    let range = (max - min).max(f64::EPSILON);
+    let points = values
+        .iter()
+        .enumerate()
+        .map(|(i, value)| {
+            let x = rect.left() + rect.width() * i as f32 / (values.len() - 1) as f32;
+            let y = rect.bottom() - rect.height() * ((value - min) / range) as f32;
+            egui::pos2(x, y)
+        })
+        .collect();
+
+    painter.add(egui::Shape::line(
+        points,
+        ui.visuals().widgets.active.fg_stroke,
+    ));
+}
@@ -0,0 +1,208 @@
+use bevy::{
+    core::FrameCount,
+    ecs::event::ManualEventReader,
+    prelude::*,
+    reflect::{FromType, GetTypeRegistration},
+    utils::HashMap,
+};
+use egui_dock::egui;
+use std::{any::TypeId, collections::VecDeque};
+
+/// How many events the Events tab keeps before dropping the oldest ones
+const MAX_LOGGED_EVENTS: usize = 1_000;
+
+/// Type data for events that can be monitored in the editor's Events tab
+///
+/// Register it with [`MonitorEventAppExt::monitor_event`], or with `#[reflect(MonitorEvent)]`.
+#[derive(Clone)]
+pub struct ReflectMonitorEvent {
+    /// Returns the events sent since the last call
+    read: fn(&mut World) -> Vec<Box<dyn Reflect>>,
+}
+
+impl<E: Event + Reflect> FromType<E> for ReflectMonitorEvent {
+    fn from_type() -> Self {
+        Self {
+            read: |world| {
+                world.init_resource::<MonitorReader<E>>();
+                world.resource_scope::<MonitorReader<E>, _>(|world, mut reader| {
+                    let Some(events) = world.get_resource::<Events<E>>() else {
+                        return Vec::new();
+                    };
+                    reader
+                        .0
+                        .read(events)
+                        .map(|event| event.clone_value())
+                        .collect()
+                })
+            },
+        }
+    }
+}
+
+#[derive(Resource)]
+struct MonitorReader<E: Event>(ManualEventReader<E>);
+
+impl<E: Event> Default for MonitorReader<E> {
+    fn default() -> Self {
+        Self(default())
+    }
+}
+
+pub trait MonitorEventAppExt {
+    /// Makes the event type available in the editor's Events tab
+    fn monitor_event<E: Event + Reflect + TypePath + GetTypeRegistration>(&mut self) -> &mut Self;
+}
+
+impl MonitorEventAppExt for App {
+    fn monitor_event<E: Event + Reflect + TypePath + GetTypeRegistration>(&mut self) -> &mut Self {
+        self.register_type::<E>()
+            .register_type_data::<E, ReflectMonitorEvent>()
+    }
+}
+
+#[derive(Resource, Default)]
+pub(crate) struct EventMonitor {
+    subscriptions: HashMap<TypeId, EventCounts>,
+    log: VecDeque<LoggedEvent>,
+    paused: bool,
+}
+
+#[derive(Default)]
+struct EventCounts {
+    this_frame: usize,
+    total: usize,
+}
+
+struct LoggedEvent {
+    frame: u32,
+    name: String,
+    event: Box<dyn Reflect>,
+}
+
+pub(crate) fn collect_monitored_events(world: &mut World) {
+    let type_registry = world.resource::<AppTypeRegistry>().0.clone();
+    let type_registry = type_registry.read();
+    let frame = world.resource::<FrameCount>().0;
+
+    let subscribed: Vec<TypeId> = world
+        .resource::<EventMonitor>()
+        .subscriptions
+        .keys()
+        .copied()
+        .collect();
+
+    for type_id in subscribed {
+        let Some(registration) = type_registry.get(type_id) else {
+            continue;
+        };
+        let Some(reflect_monitor_event) = registration.data::<ReflectMonitorEvent>() else {
+            continue;
+        };
+
+        // read even while paused, so we don't get a burst of stale events when resuming
+        let events = (reflect_monitor_event.read)(world);
+
+        let mut monitor = world.resource_mut::<EventMonitor>();
+        if monitor.paused {
+            continue;
+        }
+
+        let counts = monitor.subscriptions.entry(type_id).or_default();
+        counts.this_frame = events.len();
+        counts.total += events.len();
+
+        let name = registration.type_info().type_path_table().short_path();
+        for event in events {
+            if monitor.log.len() == MAX_LOGGED_EVENTS {
+                monitor.log.pop_front();
+            }
+            monitor.log.push_back(LoggedEvent {
+                frame,
+                name: name.to_string(),
+                event,
+            });
+        }
+    }
+}
+
+pub(crate) fn events_ui(world: &mut World, ui: &mut egui::Ui) {
+    let type_registry = world.resource::<AppTypeRegistry>().0.clone();
+    let type_registry = type_registry.read();
+
+    let mut event_types: Vec<_> = type_registry
+        .iter()
+        .filter(|registration| registration.data::<ReflectMonitorEvent>().is_some())
+        .map(|registration| {
+            (
+                registration.type_info().type_path_table().short_path(),
+                registration.type_id(),
+            )
+        })
+        .collect();
+    event_types.sort_by(|(name_a, _), (name_b, _)| name_a.cmp(name_b));
+
+    let mut monitor = world.resource_mut::<EventMonitor>();
+    let monitor = &mut *monitor;
+
+    egui::SidePanel::left("monitored_events").show_inside(ui, |ui| {
+        ui.horizontal(|ui| {
+            let pause_label = if monitor.paused { "Resume" } else { "Pause" };
+            if ui.button(pause_label).clicked() {
+                monitor.paused = !monitor.paused;
+            }
+            if ui.button("Clear").clicked() {
+                monitor.log.clear();
+                for counts in monitor.subscriptions.values_mut() {
+                    *counts = default();
+                }
+            }
+        });
+
+        ui.separator();
+
+        if event_types.is_empty() {
+            ui.label("Register events with `App::monitor_event` to monitor them here");
+        }
+
+        egui::Grid::new("event_counts").show(ui, |ui| {
+            ui.strong("Event");
+            ui.strong("Frame");
+            ui.strong("Total");
+            ui.end_row();
+
+            for (name, type_id) in event_types {
+                let mut subscribed = monitor.subscriptions.contains_key(&type_id);
+                if ui.checkbox(&mut subscribed, name).changed() {
+                    if subscribed {
+                        monitor.subscriptions.insert(type_id, default());
+                    } else {
+                        monitor.subscriptions.remove(&type_id);
+                    }
+                }
+
+                if let Some(counts) = monitor.subscriptions.get(&type_id) {
+                    ui.label(counts.this_frame.to_string());
+                    ui.label(counts.total.to_string());
+                }
+                ui.end_row();
+            }
+        });
+    });
+
+    egui::CentralPanel::default().show_inside(ui, |ui| {
+        let row_height = ui.text_style_height(&egui::TextStyle::Body);
+        egui::ScrollArea::both()
+            .auto_shrink(false)
+            .stick_to_bottom(true)
+            .show_rows(ui, row_height, monitor.log.len(), |ui, rows| {
+                for logged in monitor.log.range(rows) {
+                    ui.horizontal(|ui| {
+                        ui.weak(logged.frame.to_string());
+                        ui.strong(logged.name.as_str());
+                        ui.label(format!("{:?}", logged.event));
+                    });
+                }
+            });
+    });
+}
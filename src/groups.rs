@@ -0,0 +1,235 @@
+use crate::select_entities;
+use bevy::{
+    prelude::*,
+    reflect::{TypeInfo, TypeRegistration, TypeRegistry},
+};
+use bevy_inspector_egui::bevy_inspector::{guess_entity_name, hierarchy::SelectedEntities};
+use egui_dock::egui;
+use std::any::TypeId;
+
+/// Named groups of entities, and the component type entities are listed by in the Groups tab
+#[derive(Resource)]
+pub(crate) struct GroupsPanel {
+    groups: Vec<EntityGroup>,
+    /// Name of the next group
+    name: String,
+    component: Option<TypeId>,
+    /// Only offer components without fields, such as `Enemy` or `Checkpoint`
+    markers_only: bool,
+}
+
+impl Default for GroupsPanel {
+    fn default() -> Self {
+        Self {
+            groups: Vec::new(),
+            name: String::new(),
+            component: None,
+            markers_only: true,
+        }
+    }
+}
+
+struct EntityGroup {
+    name: String,
+    entities: Vec<Entity>,
+}
+
+enum GroupAction {
+    Add(usize),
+    Remove(usize),
+    Delete(usize),
+}
+
+fn is_marker(registration: &TypeRegistration) -> bool {
+    match registration.type_info() {
+        TypeInfo::Struct(info) => info.field_len() == 0,
+        TypeInfo::TupleStruct(info) => info.field_len() == 0,
+        _ => false,
+    }
+}
+
+/// Entities that have the component
+fn entities_with(world: &World, type_id: TypeId) -> Vec<Entity> {
+    let Some(component_id) = world.components().get_id(type_id) else {
+        return Vec::new();
+    };
+    world
+        .archetypes()
+        .iter()
+        .filter(|archetype| archetype.contains(component_id))
+        .flat_map(|archetype| archetype.entities().iter().map(|entity| entity.id()))
+        .collect()
+}
+
+/// Lists the named groups and the entities with the chosen component, returns whether the
+/// selection changed
+pub(crate) fn groups_ui(
+    world: &mut World,
+    ui: &mut egui::Ui,
+    type_registry: &TypeRegistry,
+    selected_entities: &mut SelectedEntities,
+) -> bool {
+    let mut select = None;
+
+    world.resource_scope::<GroupsPanel, _>(|world, mut panel| {
+        // forget entities that have been despawned
+        for group in &mut panel.groups {
+            group
+                .entities
+                .retain(|&entity| world.get_entity(entity).is_some());
+        }
+
+        ui.horizontal(|ui| {
+            ui.add(egui::TextEdit::singleline(&mut panel.name).hint_text("Group name"));
+            let name = panel.name.trim().to_string();
+            if ui
+                .add_enabled(
+                    !name.is_empty() && !selected_entities.is_empty(),
+                    egui::Button::new("New group"),
+                )
+                .on_hover_text("Groups the selected entities")
+                .clicked()
+            {
+                panel.groups.push(EntityGroup {
+                    name,
+                    entities: selected_entities.iter().collect(),
+                });
+                panel.name.clear();
+            }
+        });
+
+        let mut action = None;
+        egui::ScrollArea::vertical()
+            .id_source("groups")
+            .max_height(ui.available_height() / 2.)
+            .show(ui, |ui| {
+                if panel.groups.is_empty() {
+                    ui.weak("Select entities and name a group to create one");
+                }
+                for (index, group) in panel.groups.iter().enumerate() {
+                    egui::CollapsingHeader::new(format!(
+                        "{} ({})",
+                        group.name,
+                        group.entities.len()
+                    ))
+                    .id_source(("group", index))
+                    .show(ui, |ui| {
+                        ui.horizontal(|ui| {
+                            if ui.small_button("Select").clicked() {
+                                select = Some(group.entities.clone());
+                            }
+                            if ui
+                                .small_button("Add selection")
+                                .on_hover_text("Adds the selected entities to the group")
+                                .clicked()
+                            {
+                                action = Some(GroupAction::Add(index));
+                            }
+                            if ui
+                                .small_button("Remove selection")
+                                .on_hover_text("Removes the selected entities from the group")
+                                .clicked()
+                            {
+                                action = Some(GroupAction::Remove(index));
+                            }
+                            if ui.small_button("Delete").clicked() {
+                                action = Some(GroupAction::Delete(index));
+                            }
+                        });
+                        for &entity in &group.entities {
+                            let selected = selected_entities.contains(entity);
+                            if ui
+                                .selectable_label(selected, guess_entity_name(world, entity))
+                                .clicked()
+                            {
+                                select = Some(vec![entity]);
+                            }
+                        }
+                    });
+                }
+            });
+
+        match action {
+            Some(GroupAction::Add(index)) => {
+                let group = &mut panel.groups[index];
+                for entity in selected_entities.iter() {
+                    if !group.entities.contains(&entity) {
+                        group.entities.push(entity);
+                    }
+                }
+            }
+            Some(GroupAction::Remove(index)) => {
+                panel.groups[index]
+                    .entities
+                    .retain(|&entity| !selected_entities.contains(entity));
+            }
+            Some(GroupAction::Delete(index)) => {
+                panel.groups.remove(index);
+            }
+            None => {}
+        }
+
+        ui.separator();
+        ui.horizontal(|ui| {
+            ui.strong("By component");
+            ui.checkbox(&mut panel.markers_only, "Markers only")
+                .on_hover_text("Only list components without fields");
+        });
+
+        // components that have been added to an entity at some point
+        let mut components: Vec<_> = type_registry
+            .iter()
+            .filter(|registration| registration.data::<ReflectComponent>().is_some())
+            .filter(|registration| world.components().get_id(registration.type_id()).is_some())
+            .filter(|registration| !panel.markers_only || is_marker(registration))
+            .map(|registration| {
+                (
+                    registration.type_id(),
+                    registration.type_info().type_path_table().short_path(),
+                )
+            })
+            .collect();
+        components.sort_by_key(|(_, name)| *name);
+
+        let selected_name = panel
+            .component
+            .and_then(|type_id| components.iter().find(|(id, _)| *id == type_id))
+            .map_or("Choose a component", |(_, name)| *name);
+        egui::ComboBox::from_id_source("group_component")
+            .selected_text(selected_name)
+            .show_ui(ui, |ui| {
+                for (type_id, name) in &components {
+                    ui.selectable_value(&mut panel.component, Some(*type_id), *name);
+                }
+            });
+
+        let Some(component) = panel.component else {
+            return;
+        };
+        let entities = entities_with(world, component);
+        if ui
+            .add_enabled(
+                !entities.is_empty(),
+                egui::Button::new(format!("Select all ({})", entities.len())),
+            )
+            .clicked()
+        {
+            select = Some(entities.clone());
+        }
+        egui::ScrollArea::vertical()
+            .id_source("component_entities")
+            .show(ui, |ui| {
+                for entity in entities {
+                    let selected = selected_entities.contains(entity);
+                    if ui
+                        .selectable_label(selected, guess_entity_name(world, entity))
+                        .clicked()
+                    {
+                        select = Some(vec![entity]);
+                    }
+                }
+            });
+    });
+
+    select.is_some_and(|entities| select_entities(selected_entities, entities))
+}
@@ -0,0 +1,232 @@
+use crate::{select_entities, Ed2dCamera, Ed2dCameraSettings, GizmoLayers, UiState};
+use bevy::{prelude::*, render::view::RenderLayers};
+use bevy_inspector_egui::bevy_inspector::hierarchy::SelectedEntities;
+use egui_dock::egui;
+
+/// How far the selection moves in z per key press or button click
+const Z_STEP: f32 = 1.;
+
+/// Depths hidden from the Layers tab
+#[derive(Resource, Default)]
+pub(crate) struct LayersPanel {
+    hidden_depths: Vec<HiddenDepth>,
+}
+
+/// Entities hidden because of their z, and the visibility to restore when shown again
+struct HiddenDepth {
+    z: f32,
+    entities: Vec<(Entity, Visibility)>,
+}
+
+/// Moves the selected entities towards the camera with Page Up, and away with Page Down
+pub(crate) fn depth_keys(
+    keys: Res<ButtonInput<KeyCode>>,
+    ui_state: Res<UiState>,
+    mut transforms: Query<&mut Transform, Without<Ed2dCamera>>,
+) {
+    if ui_state.hovered_viewport().is_none() {
+        return;
+    }
+    let direction =
+        keys.just_pressed(KeyCode::PageUp) as i8 - keys.just_pressed(KeyCode::PageDown) as i8;
+    if direction == 0 {
+        return;
+    }
+
+    for entity in ui_state.selected_entities.iter() {
+        if let Ok(mut transform) = transforms.get_mut(entity) {
+            transform.translation.z += direction as f32 * Z_STEP;
+        }
+    }
+}
+
+/// Entities on one depth or render layer
+struct LayerEntry<T> {
+    layer: T,
+    entities: Vec<Entity>,
+}
+
+fn add_to_layer<T: PartialEq>(layers: &mut Vec<LayerEntry<T>>, layer: T, entity: Entity) {
+    match layers.iter_mut().find(|entry| entry.layer == layer) {
+        Some(entry) => entry.entities.push(entity),
+        None => layers.push(LayerEntry {
+            layer,
+            entities: vec![entity],
+        }),
+    }
+}
+
+/// Lists the depths and render layers in use, returns whether the selection changed
+pub(crate) fn layers_ui(
+    world: &mut World,
+    ui: &mut egui::Ui,
+    selected_entities: &mut SelectedEntities,
+) -> bool {
+    // the editor's own layers can't be toggled, they would show one view's gizmos in the others
+    let gizmo_layers = world.resource::<GizmoLayers>().reserved();
+
+    let mut depths = Vec::new();
+    let mut render_layers = Vec::new();
+    for (entity, transform, layers) in world
+        .query_filtered::<(Entity, &Transform, Option<&RenderLayers>), (With<Visibility>, Without<Ed2dCamera>)>()
+        .iter(world)
+    {
+        add_to_layer(&mut depths, transform.translation.z, entity);
+        for layer in layers.unwrap_or_default().iter() {
+            if gizmo_layers.contains(&layer) {
+                continue;
+            }
+            add_to_layer(&mut render_layers, layer, entity);
+        }
+    }
+
+    // hidden entities may have been moved or despawned since, they can still be shown again
+    for hidden in &world.resource::<LayersPanel>().hidden_depths {
+        if !depths.iter().any(|entry| entry.layer == hidden.z) {
+            depths.push(LayerEntry {
+                layer: hidden.z,
+                entities: Vec::new(),
+            });
+        }
+    }
+    // the layers the editor cameras see are listed even if nothing is on them
+    for layer in world.resource::<Ed2dCameraSettings>().render_layers.iter() {
+        if gizmo_layers.contains(&layer) {
+            continue;
+        }
+        if !render_layers.iter().any(|entry| entry.layer == layer) {
+            render_layers.push(LayerEntry {
+                layer,
+                entities: Vec::new(),
+            });
+        }
+    }
+
+    // front to back
+    depths.sort_by(|a, b| b.layer.total_cmp(&a.layer));
+    render_layers.sort_by_key(|entry| entry.layer);
+
+    let mut select = None;
+
+    egui::ScrollArea::vertical().show(ui, |ui| {
+        ui.horizontal(|ui| {
+            ui.strong("Depth");
+            ui.add_enabled_ui(!selected_entities.is_empty(), |ui| {
+                if ui
+                    .small_button("Forward")
+                    .on_hover_text("Move the selection forward (Page Up)")
+                    .clicked()
+                {
+                    move_selection(world, selected_entities, Z_STEP);
+                }
+                if ui
+                    .small_button("Back")
+                    .on_hover_text("Move the selection back (Page Down)")
+                    .clicked()
+                {
+                    move_selection(world, selected_entities, -Z_STEP);
+                }
+            });
+        });
+        egui::Grid::new("depths").striped(true).show(ui, |ui| {
+            for entry in &depths {
+                let mut visible = !world
+                    .resource::<LayersPanel>()
+                    .hidden_depths
+                    .iter()
+                    .any(|hidden| hidden.z == entry.layer);
+                if ui
+                    .checkbox(&mut visible, "")
+                    .on_hover_text("Show entities at this z")
+                    .changed()
+                {
+                    set_depth_visible(world, entry, visible);
+                }
+                ui.label(format!("z = {}", entry.layer));
+                ui.weak(entry.entities.len().to_string());
+                if ui.small_button("Select").clicked() {
+                    select = Some(entry.entities.clone());
+                }
+                ui.end_row();
+            }
+        });
+
+        ui.separator();
+        ui.strong("Render layers");
+        egui::Grid::new("render_layers")
+            .striped(true)
+            .show(ui, |ui| {
+                for entry in &render_layers {
+                    let layers = world.resource::<Ed2dCameraSettings>().render_layers.clone();
+                    let mut visible = layers.intersects(&RenderLayers::layer(entry.layer));
+                    if ui
+                        .checkbox(&mut visible, "")
+                        .on_hover_text("Show this layer in the game views")
+                        .changed()
+                    {
+                        let mut settings = world.resource_mut::<Ed2dCameraSettings>();
+                        settings.render_layers = if visible {
+                            layers.with(entry.layer)
+                        } else {
+                            layers.without(entry.layer)
+                        };
+                    }
+                    ui.label(format!("Layer {}", entry.layer));
+                    ui.weak(entry.entities.len().to_string());
+                    if ui.small_button("Select").clicked() {
+                        select = Some(entry.entities.clone());
+                    }
+                    ui.end_row();
+                }
+            });
+    });
+
+    select.is_some_and(|entities| select_entities(selected_entities, entities))
+}
+
+fn move_selection(world: &mut World, selected_entities: &SelectedEntities, offset: f32) {
+    for entity in selected_entities.iter() {
+        if let Some(mut transform) = world.get_mut::<Transform>(entity) {
+            transform.translation.z += offset;
+        }
+    }
+}
+
+/// Hides the entities at a depth, or restores their visibility
+fn set_depth_visible(world: &mut World, entry: &LayerEntry<f32>, visible: bool) {
+    if visible {
+        let mut panel = world.resource_mut::<LayersPanel>();
+        let Some(index) = panel
+            .hidden_depths
+            .iter()
+            .position(|hidden| hidden.z == entry.layer)
+        else {
+            return;
+        };
+        let hidden = panel.hidden_depths.remove(index);
+        for (entity, visibility) in hidden.entities {
+            if let Some(mut current) = world.get_mut::<Visibility>(entity) {
+                *current = visibility;
+            }
+        }
+    } else {
+        let entities = entry
+            .entities
+            .iter()
+            .filter_map(|&entity| {
+                let mut visibility = world.get_mut::<Visibility>(entity)?;
+                Some((
+                    entity,
+                    std::mem::replace(&mut *visibility, Visibility::Hidden),
+                ))
+            })
+            .collect();
+        world
+            .resource_mut::<LayersPanel>()
+            .hidden_depths
+            .push(HiddenDepth {
+                z: entry.layer,
+                entities,
+            });
+    }
+}
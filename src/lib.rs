@@ -1,17 +1,21 @@
 use backend::{HitData, PointerHits};
 use bevy::{
-    asset::{ReflectAsset, UntypedAssetId},
+    asset::UntypedAssetId,
     color::palettes,
+    diagnostic::{EntityCountDiagnosticsPlugin, FrameTimeDiagnosticsPlugin},
     prelude::*,
-    reflect::TypeRegistry,
     render::{
-        camera::{CameraUpdateSystem, NormalizedRenderTarget, Viewport},
+        camera::{CameraUpdateSystem, NormalizedRenderTarget, RenderTarget, ScalingMode, Viewport},
         primitives::Aabb,
+        render_resource::{
+            Extent3d, TextureDescriptor, TextureDimension, TextureFormat, TextureUsages,
+        },
+        view::RenderLayers,
     },
-    window::PrimaryWindow,
+    window::{PrimaryWindow, WindowRef},
 };
 use bevy_inspector_egui::{
-    bevy_egui::{self, EguiContext, EguiPlugin, EguiSet},
+    bevy_egui::{self, EguiContext, EguiPlugin, EguiSet, EguiUserTextures},
     bevy_inspector::{
         self,
         hierarchy::{hierarchy_ui, SelectedEntities, SelectionMode},
@@ -20,27 +24,95 @@ use bevy_inspector_egui::{
     DefaultInspectorConfigPlugin,
 };
 use bevy_mod_picking::prelude::*;
-use bevy_pancam::{PanCam, PanCamPlugin};
 use egui_dock::{
     egui::{self, Sense},
-    DockArea, DockState, NodeIndex,
+    DockArea, DockState, NodeIndex, SurfaceIndex,
 };
-use std::any::TypeId;
+use std::{any::TypeId, ops::Range};
+
+mod align;
+mod asset_browser;
+mod assets;
+mod atlas;
+mod camera;
+mod console;
+mod diagnostics;
+mod events;
+mod groups;
+mod layers;
+mod measure;
+mod minimap;
+mod overlays;
+mod prefabs;
+mod resources;
+mod sprites;
+mod states;
+mod systems;
+mod tilemap;
+
+pub use camera::Ed2dCameraSettings;
+pub use console::log_layer;
+pub use events::{MonitorEventAppExt, ReflectMonitorEvent};
+pub use overlays::{OverlayAppExt, OverlayGizmos};
+pub use prefabs::PrefabInstance;
+pub use states::{InspectStateAppExt, ReflectInspectState};
+pub use tilemap::Tile;
 
 pub struct Ed2dPlugin {
     pub auto_add_pickables: bool,
+    /// Which window the editor is shown in
+    pub window: EditorWindow,
+    /// Zoom and panning limits of the editor camera
+    pub camera: Ed2dCameraSettings,
+    /// How the game view shows what the editor camera sees
+    pub game_view: GameViewMode,
+    /// First of the render layers reserved for the editor's gizmos, one for each game view and
+    /// one for overlays
+    ///
+    /// Game views don't show the game's entities on these layers, so the game shouldn't use them.
+    pub first_gizmo_layer: usize,
 }
 
 impl Default for Ed2dPlugin {
     fn default() -> Self {
         Self {
             auto_add_pickables: true,
+            window: default(),
+            camera: default(),
+            game_view: default(),
+            first_gizmo_layer: 27,
         }
     }
 }
 
+/// How the game view shows what the editor camera sees
+#[derive(Clone, Copy, Default, PartialEq, Eq)]
+pub enum GameViewMode {
+    /// Shrink the camera's viewport to the game view, rendering straight to the window
+    #[default]
+    CameraViewport,
+    /// Render the camera to an image shown in the game view, so the game view can be floated or
+    /// overlapped by other tabs
+    Texture,
+}
+
+/// Where the editor ui is shown
+#[derive(Clone, Default)]
+pub enum EditorWindow {
+    /// Show the editor on top of the primary window
+    #[default]
+    Primary,
+    /// Open the editor in an OS window of its own, leaving the primary window untouched
+    Separate(Window),
+}
+
 impl Plugin for Ed2dPlugin {
     fn build(&self, app: &mut App) {
+        // the gizmo config groups keep their render layers, so these can't change at runtime
+        let gizmo_layers = GizmoLayers {
+            first: self.first_gizmo_layer,
+        };
+
         // if !app.is_plugin_added::<DefaultPickingPlugins>() {
         app.add_plugins(DefaultPickingPlugins);
         // }
@@ -50,116 +122,483 @@ impl Plugin for Ed2dPlugin {
         if !app.is_plugin_added::<EguiPlugin>() {
             app.add_plugins(EguiPlugin);
         }
-        if !app.is_plugin_added::<PanCamPlugin>() {
-            app.add_plugins(PanCamPlugin);
+
+        match &self.window {
+            EditorWindow::Primary => {
+                app.add_systems(PreStartup, mark_primary_window);
+            }
+            EditorWindow::Separate(window) => {
+                app.world_mut().spawn((window.clone(), Ed2dWindow));
+            }
         }
 
         app.add_systems(Startup, setup)
             .add_systems(First, add_no_deselect)
-            .add_systems(Update, toggle_active)
+            // schedules can only be inspected while they're not running
+            .add_systems(PreStartup, systems::inspect_schedules)
+            .add_systems(First, systems::inspect_schedules)
+            .add_systems(Last, systems::inspect_schedules)
+            .add_systems(Last, events::collect_monitored_events)
+            .add_systems(Last, states::record_state_transitions)
+            .add_systems(Update, (toggle_active, console::collect_log_entries))
             .add_systems(
                 Update,
                 (
-                    select_clicked,
-                    handle_deselect_events,
-                    focus_selected_object,
+                    prefabs::spawn_pending_prefabs,
+                    prefabs::resolve_prefab_handles,
+                ),
+            )
+            .add_systems(
+                Update,
+                (
+                    FrameTimeDiagnosticsPlugin::diagnostic_system
+                        .run_if(diagnostics::frame_time_enabled),
+                    EntityCountDiagnosticsPlugin::diagnostic_system
+                        .run_if(diagnostics::entity_count_enabled),
+                ),
+            )
+            .add_systems(
+                Update,
+                (
+                    (select_clicked, handle_deselect_events)
+                        .run_if(not(measure::measuring).and_then(not(tilemap::painting))),
+                    (
+                        focus_selected_object,
+                        camera::camera_navigation_keys,
+                        camera::keyboard_camera_controls.before(camera::constrain_camera),
+                        layers::depth_keys,
+                    )
+                        .run_if(not(typing)),
                 )
                     .run_if(is_ui_active),
             )
+            .add_systems(Startup, camera::load_bookmarks)
             .add_systems(
                 Update,
-                (show_ui_system, update_pick_selections, toggle_pancam)
+                (camera::animate_camera, camera::constrain_camera).chain(),
+            )
+            .add_systems(
+                Update,
+                (show_ui_system, update_pick_selections)
                     .chain()
                     .run_if(is_ui_active)
                     .before(EguiSet::ProcessOutput)
                     .before(bevy::transform::TransformSystem::TransformPropagate),
             )
-            .add_systems(PostUpdate, set_camera_viewport.after(show_ui_system))
+            // both set the camera targets, the texture mode overrides the viewport mode's targets
+            .add_systems(
+                PostUpdate,
+                (set_camera_viewport, set_camera_texture)
+                    .chain()
+                    .after(show_ui_system),
+            )
             .add_systems(PostUpdate, editor_picking)
-            // grid gizmo needs to be drawn after the camera has been updated, so the projection height is correct
-            .add_systems(PostUpdate, draw_grid_gizmo.after(CameraUpdateSystem))
-            .add_systems(PostUpdate, draw_transform_gizmos.after(draw_grid_gizmo))
-            .init_resource::<UiState>()
+            .add_systems(PostUpdate, texture_picking)
+            .insert_resource(UiState {
+                game_view_mode: self.game_view,
+                ..default()
+            })
+            .init_resource::<assets::AssetsPanel>()
+            .init_resource::<asset_browser::AssetBrowser>()
+            .init_resource::<prefabs::PrefabsPanel>()
+            .register_type::<PrefabInstance>()
+            .register_type::<prefabs::PrefabHandles>()
+            .init_resource::<console::Console>()
+            .init_resource::<diagnostics::EditorDiagnostics>()
+            .init_resource::<systems::SystemsPanel>()
+            .init_resource::<systems::DisabledSystemSets>()
+            .init_resource::<events::EventMonitor>()
+            .init_resource::<states::StatesPanel>()
+            .init_resource::<measure::Measurement>()
+            .init_resource::<overlays::Overlays>()
+            .init_resource::<layers::LayersPanel>()
+            .init_resource::<groups::GroupsPanel>()
+            .init_resource::<sprites::SpriteTools>()
+            .init_resource::<atlas::AtlasGrid>()
+            .init_resource::<tilemap::TilePainter>()
+            .register_type::<Tile>()
+            .init_resource::<camera::CameraBookmarks>()
+            .insert_resource(self.camera.clone())
+            .insert_resource(gizmo_layers)
+            .register_type::<Ed2dCameraSettings>()
             .add_event::<EditorEntitySelectionChanged>();
 
+        overlays::init_overlay_gizmos(app);
+        app.world_mut()
+            .resource_mut::<GizmoConfigStore>()
+            .config_mut::<OverlayGizmos>()
+            .0
+            .render_layers = RenderLayers::layer(gizmo_layers.overlay());
+        add_viewport_gizmos::<Viewport1Gizmos>(app, gizmo_layers);
+        add_viewport_gizmos::<Viewport2Gizmos>(app, gizmo_layers);
+        add_viewport_gizmos::<Viewport3Gizmos>(app, gizmo_layers);
+        add_viewport_gizmos::<Viewport4Gizmos>(app, gizmo_layers);
+
         if self.auto_add_pickables {
             app.add_systems(Update, auto_add_pickables);
         }
     }
 }
 
+/// Draws the editor's gizmos in one game view, so they can depend on that view's camera
+fn add_viewport_gizmos<G: ViewportGizmos>(app: &mut App, gizmo_layers: GizmoLayers) {
+    app.insert_gizmo_config(
+        G::default(),
+        GizmoConfig {
+            render_layers: RenderLayers::layer(gizmo_layers.viewport(G::VIEWPORT)),
+            ..default()
+        },
+    )
+    // grid gizmo needs to be drawn after the camera has been updated, so the projection height is correct
+    .add_systems(
+        PostUpdate,
+        (
+            draw_grid_gizmo::<G>,
+            draw_transform_gizmos::<G>,
+            measure::draw_measurement_gizmo::<G>,
+        )
+            .chain()
+            .after(CameraUpdateSystem),
+    );
+}
+
+/// How many game views can be open at once, each needs a gizmo config group and render layer of
+/// its own
+const MAX_VIEWPORTS: usize = 4;
+
+/// Render layers the editor draws its gizmos on, set from [`Ed2dPlugin::first_gizmo_layer`]
+#[derive(Resource, Clone, Copy)]
+struct GizmoLayers {
+    first: usize,
+}
+
+impl GizmoLayers {
+    /// All of the editor's layers, one for each game view and one for overlays
+    fn reserved(self) -> Range<usize> {
+        self.first..self.overlay() + 1
+    }
+
+    /// Render layer of the gizmos only shown in the game view at `index`
+    fn viewport(self, index: usize) -> usize {
+        self.first + index
+    }
+
+    /// Render layer of [`OverlayGizmos`]
+    fn overlay(self) -> usize {
+        self.first + MAX_VIEWPORTS
+    }
+}
+
+/// Gizmo config group only shown in one game view
+trait ViewportGizmos: GizmoConfigGroup {
+    /// Index of the game view in [`UiState::viewports`]
+    const VIEWPORT: usize;
+}
+
+macro_rules! viewport_gizmos {
+    ($($name:ident = $viewport:literal),*) => {
+        $(
+            #[derive(Default, Reflect, GizmoConfigGroup)]
+            struct $name;
+
+            impl ViewportGizmos for $name {
+                const VIEWPORT: usize = $viewport;
+            }
+        )*
+    };
+}
+
+viewport_gizmos!(
+    Viewport1Gizmos = 0,
+    Viewport2Gizmos = 1,
+    Viewport3Gizmos = 2,
+    Viewport4Gizmos = 3
+);
+
 #[derive(Event)]
 struct EditorEntitySelectionChanged;
 
 #[derive(Component)]
 struct Ed2dCamera;
 
-fn setup(mut commands: Commands) {
+/// The window the main editor dock is shown in
+#[derive(Component)]
+struct Ed2dWindow;
+
+fn mark_primary_window(mut commands: Commands, primary_window: Query<Entity, With<PrimaryWindow>>) {
+    if let Ok(window) = primary_window.get_single() {
+        commands.entity(window).insert(Ed2dWindow);
+    }
+}
+
+fn setup(
+    mut commands: Commands,
+    editor_window: Query<Entity, With<Ed2dWindow>>,
+    settings: Res<Ed2dCameraSettings>,
+    gizmo_layers: Res<GizmoLayers>,
+    mut ui_state: ResMut<UiState>,
+) {
+    let target = editor_window
+        .get_single()
+        .map(|window| RenderTarget::Window(WindowRef::Entity(window)))
+        .unwrap_or_default();
+
     // Camera
-    commands.spawn((
-        Camera2dBundle::default(),
-        Ed2dCamera,
-        PanCam {
-            grab_buttons: vec![MouseButton::Middle, MouseButton::Right],
+    let layers = viewport_render_layers(&settings, *gizmo_layers, 0, true);
+    let camera = commands.spawn(viewport_camera(0, target, layers)).id();
+    ui_state.viewports.push(GameViewport::new(camera));
+}
+
+/// Camera for the game view at `index` in [`UiState::viewports`]
+fn viewport_camera(index: usize, target: RenderTarget, render_layers: RenderLayers) -> impl Bundle {
+    (
+        Camera2dBundle {
+            camera: Camera {
+                target,
+                order: index as isize,
+                ..default()
+            },
             ..default()
         },
-    ));
+        Ed2dCamera,
+        // synced with `Ed2dCameraSettings::render_layers` by `set_camera_viewport`
+        render_layers,
+        camera::CameraNavigation::default(),
+    )
 }
 
-// make camera only render to view not obstructed by UI
+/// The game's layers, the game view's own gizmos, and optionally the overlays
+fn viewport_render_layers(
+    settings: &Ed2dCameraSettings,
+    gizmo_layers: GizmoLayers,
+    index: usize,
+    show_overlays: bool,
+) -> RenderLayers {
+    // the gizmo layers of the other game views mustn't show up in this one
+    let layers = gizmo_layers
+        .reserved()
+        .fold(settings.render_layers.clone(), RenderLayers::without)
+        .with(gizmo_layers.viewport(index));
+    if show_overlays {
+        layers.with(gizmo_layers.overlay())
+    } else {
+        layers
+    }
+}
+
+// make cameras only render to view not obstructed by UI
 fn set_camera_viewport(
     ui_state: Res<UiState>,
-    primary_window: Query<&mut Window, With<PrimaryWindow>>,
+    windows: Query<&Window>,
+    editor_window: Query<Entity, With<Ed2dWindow>>,
     egui_settings: Res<bevy_egui::EguiSettings>,
-    mut cameras: Query<&mut Camera, With<Ed2dCamera>>,
+    settings: Res<Ed2dCameraSettings>,
+    gizmo_layers: Res<GizmoLayers>,
+    mut cameras: Query<
+        (&mut Camera, &mut OrthographicProjection, &mut RenderLayers),
+        With<Ed2dCamera>,
+    >,
 ) {
-    let mut cam = cameras.single_mut();
-
-    let Ok(window) = primary_window.get_single() else {
+    let Ok(editor_window) = editor_window.get_single() else {
         return;
     };
 
-    if !ui_state.active {
-        cam.viewport = None;
+    for (index, viewport) in ui_state.viewports.iter().enumerate() {
+        let Ok((mut cam, mut projection, mut render_layers)) = cameras.get_mut(viewport.camera)
+        else {
+            continue;
+        };
+
+        let layers =
+            viewport_render_layers(&settings, *gizmo_layers, index, viewport.show_overlays);
+        if *render_layers != layers {
+            *render_layers = layers;
+        }
+
+        // game views that aren't shown don't need rendering, and while the editor is hidden the
+        // first camera shows the game in the editor window
+        let is_active = if ui_state.active {
+            viewport.window.is_some()
+        } else {
+            index == 0
+        };
+        if cam.is_active != is_active {
+            cam.is_active = is_active;
+        }
+        if !is_active {
+            continue;
+        }
+
+        if ui_state.game_view_mode == GameViewMode::Texture && ui_state.active {
+            // handled by `set_camera_texture`
+            continue;
+        }
+        // the window's scale factor is already part of the viewport's logical size
+        set_pixels_per_unit(&mut projection, 1.);
+
+        // the game view may have been popped out to a window of its own
+        let target_window = viewport
+            .window
+            .filter(|_| ui_state.active)
+            .unwrap_or(editor_window);
+
+        let target = RenderTarget::Window(WindowRef::Entity(target_window));
+        if cam.target.normalize(None) != target.normalize(None) {
+            cam.target = target;
+        }
+
+        let Ok(window) = windows.get(target_window) else {
+            continue;
+        };
+
+        if !ui_state.active {
+            cam.viewport = None;
+            continue;
+        }
+
+        let scale_factor = window.scale_factor() * egui_settings.scale_factor;
+
+        let viewport_pos = viewport.rect.left_top().to_vec2() * scale_factor;
+        let viewport_size = viewport.rect.size() * scale_factor;
+
+        let physical_position = UVec2::new(viewport_pos.x as u32, viewport_pos.y as u32);
+        let physical_size = UVec2::new(viewport_size.x as u32, viewport_size.y as u32);
+
+        // The desired viewport rectangle at its offset in "physical pixel space"
+        let rect = physical_position + physical_size;
+
+        let window_size = window.physical_size();
+        // wgpu will panic if trying to set a viewport rect which has coordinates extending
+        // past the size of the render target, i.e. the physical window in our case.
+        // Typically this shouldn't happen- but during init and resizing etc. edge cases might occur.
+        // Simply do nothing in those cases.
+        if rect.x <= window_size.x && rect.y <= window_size.y {
+            cam.viewport = Some(Viewport {
+                physical_position,
+                physical_size,
+                depth: 0.0..1.0,
+            });
+        }
+    }
+}
+
+// render the cameras to images the size of their game views, which are shown in the game views
+fn set_camera_texture(
+    mut ui_state: ResMut<UiState>,
+    windows: Query<&Window>,
+    egui_settings: Res<bevy_egui::EguiSettings>,
+    mut images: ResMut<Assets<Image>>,
+    mut cameras: Query<(&mut Camera, &mut OrthographicProjection), With<Ed2dCamera>>,
+) {
+    if ui_state.game_view_mode != GameViewMode::Texture || !ui_state.active {
         return;
     }
 
-    let scale_factor = window.scale_factor() * egui_settings.scale_factor;
+    for viewport in &mut ui_state.viewports {
+        let Some(Ok(window)) = viewport.window.map(|window| windows.get(window)) else {
+            continue;
+        };
+        let Ok((mut cam, mut projection)) = cameras.get_mut(viewport.camera) else {
+            continue;
+        };
 
-    let viewport_pos = ui_state.viewport_rect.left_top().to_vec2() * scale_factor;
-    let viewport_size = ui_state.viewport_rect.size() * scale_factor;
+        // the image has the physical size of the game view so it stays sharp, and the window's
+        // scale factor goes into the projection so zoom levels match the viewport mode
+        let physical_size =
+            viewport.rect.size() * window.scale_factor() * egui_settings.scale_factor;
+        let size = Extent3d {
+            width: (physical_size.x as u32).max(1),
+            height: (physical_size.y as u32).max(1),
+            depth_or_array_layers: 1,
+        };
+        set_pixels_per_unit(&mut projection, window.scale_factor());
+
+        let texture = match &viewport.texture {
+            Some(texture) => {
+                if let Some(image) = images.get_mut(texture) {
+                    if image.texture_descriptor.size != size {
+                        image.resize(size);
+                    }
+                }
+                texture.clone()
+            }
+            None => {
+                let texture = images.add(viewport_image(size));
+                viewport.texture = Some(texture.clone());
+                texture
+            }
+        };
 
-    let physical_position = UVec2::new(viewport_pos.x as u32, viewport_pos.y as u32);
-    let physical_size = UVec2::new(viewport_size.x as u32, viewport_size.y as u32);
+        let target = RenderTarget::Image(texture);
+        if cam.target.normalize(None) != target.normalize(None) {
+            cam.target = target;
+        }
+        if cam.viewport.is_some() {
+            cam.viewport = None;
+        }
+    }
+}
 
-    // The desired viewport rectangle at its offset in "physical pixel space"
-    let rect = physical_position + physical_size;
+/// Sets how many pixels of the camera's render target make up one world unit at scale 1
+fn set_pixels_per_unit(projection: &mut Mut<OrthographicProjection>, pixels: f32) {
+    if !matches!(projection.scaling_mode, ScalingMode::WindowSize(current) if current == pixels) {
+        projection.scaling_mode = ScalingMode::WindowSize(pixels);
+    }
+}
 
-    let window_size = window.physical_size();
-    // wgpu will panic if trying to set a viewport rect which has coordinates extending
-    // past the size of the render target, i.e. the physical window in our case.
-    // Typically this shouldn't happen- but during init and resizing etc. edge cases might occur.
-    // Simply do nothing in those cases.
-    if rect.x <= window_size.x && rect.y <= window_size.y {
-        cam.viewport = Some(Viewport {
-            physical_position,
-            physical_size,
-            depth: 0.0..1.0,
-        });
+/// Pixels of the camera's render target per world unit at scale 1, see [`set_pixels_per_unit`]
+fn pixels_per_unit(world: &World, camera: Entity) -> f32 {
+    match world.get::<OrthographicProjection>(camera) {
+        Some(OrthographicProjection {
+            scaling_mode: ScalingMode::WindowSize(pixels),
+            ..
+        }) => *pixels,
+        _ => 1.,
     }
 }
 
-fn show_ui_system(world: &mut World) {
-    let Ok(egui_context) = world
-        .query_filtered::<&mut EguiContext, With<PrimaryWindow>>()
-        .get_single(world)
-    else {
-        return;
+/// Pixels of the camera's render target per logical pixel of the game view
+///
+/// In [`GameViewMode::Texture`] the image has physical pixels, which `set_camera_texture` makes
+/// up for in the projection.
+fn game_view_scale_factor(world: &World, camera: Entity) -> f32 {
+    let egui_scale_factor = world.resource::<bevy_egui::EguiSettings>().scale_factor;
+    egui_scale_factor * pixels_per_unit(world, camera)
+}
+
+fn viewport_image(size: Extent3d) -> Image {
+    let mut image = Image {
+        texture_descriptor: TextureDescriptor {
+            label: Some("ed2d_game_view"),
+            size,
+            dimension: TextureDimension::D2,
+            format: TextureFormat::Bgra8UnormSrgb,
+            mip_level_count: 1,
+            sample_count: 1,
+            usage: TextureUsages::TEXTURE_BINDING
+                | TextureUsages::COPY_DST
+                | TextureUsages::RENDER_ATTACHMENT,
+            view_formats: &[],
+        },
+        ..default()
     };
-    let mut egui_context = egui_context.clone();
+    // fill the image with zeroes
+    image.resize(size);
+    image
+}
+
+fn show_ui_system(world: &mut World) {
+    let egui_contexts: Vec<_> = world
+        .query::<(Entity, &mut EguiContext)>()
+        .iter_mut(world)
+        .map(|(window, mut egui_context)| (window, egui_context.get_mut().clone()))
+        .collect();
 
     world.resource_scope::<UiState, _>(|world, mut ui_state| {
-        ui_state.ui(world, egui_context.get_mut())
+        ui_state.ui(world, &egui_contexts);
+        ui_state.typing = egui_contexts
+            .iter()
+            .any(|(_, context)| context.wants_keyboard_input());
     });
 }
 
@@ -174,25 +613,60 @@ enum InspectorSelection {
 struct UiState {
     active: bool,
     state: DockState<EguiWindow>,
-    viewport_rect: egui::Rect,
-    viewport_hovered: bool,
+    /// Game views by their index in [`EguiWindow::GameView`]
+    viewports: Vec<GameViewport>,
+    /// The game view that was hovered last
+    active_viewport: usize,
+    /// Whether assets dropped in the game view are placed on the grid
+    snap_to_grid: bool,
+    game_view_mode: GameViewMode,
+    /// Tabs that have been popped out to OS windows of their own
+    detached_tabs: Vec<(Entity, EguiWindow)>,
     selected_entities: SelectedEntities,
     selection: InspectorSelection,
+    /// Whether a text field in the editor has keyboard focus
+    typing: bool,
     // gizmo_mode: GizmoMode,
 }
 
 impl Default for UiState {
     fn default() -> Self {
-        let mut state = DockState::new(vec![EguiWindow::GameView]);
+        let mut state = DockState::new(vec![EguiWindow::GameView(0)]);
         let tree = state.main_surface_mut();
         let [game, _inspector] =
             tree.split_right(NodeIndex::root(), 0.75, vec![EguiWindow::Inspector]);
-        let [_game, hierarchy] = tree.split_right(game, 0.75, vec![EguiWindow::Hierarchy]);
+        let [game, hierarchy] = tree.split_right(
+            game,
+            0.75,
+            vec![
+                EguiWindow::Hierarchy,
+                EguiWindow::Minimap,
+                EguiWindow::Layers,
+                EguiWindow::Groups,
+                EguiWindow::Tiles,
+            ],
+        );
+        let [_game, _bottom] = tree.split_below(
+            game,
+            0.75,
+            vec![
+                EguiWindow::Log,
+                EguiWindow::Diagnostics,
+                EguiWindow::Systems,
+                EguiWindow::Events,
+                EguiWindow::States,
+            ],
+        );
 
         let [_hierarchy, _resources_and_assets] = tree.split_below(
             hierarchy,
             0.35,
-            vec![EguiWindow::Resources, EguiWindow::Assets],
+            vec![
+                EguiWindow::Resources,
+                EguiWindow::Assets,
+                EguiWindow::Files,
+                EguiWindow::Prefabs,
+            ],
         );
 
         Self {
@@ -200,47 +674,316 @@ impl Default for UiState {
             state,
             selected_entities: SelectedEntities::default(),
             selection: InspectorSelection::Entities,
-            viewport_rect: egui::Rect::NOTHING,
-            viewport_hovered: false,
+            viewports: Vec::new(),
+            active_viewport: 0,
+            snap_to_grid: false,
+            game_view_mode: default(),
+            detached_tabs: Vec::new(),
+            typing: false,
             // gizmo_mode: GizmoMode::Translate,
         }
     }
 }
 
+/// A game view tab, and the camera shown in it
+struct GameViewport {
+    camera: Entity,
+    rect: egui::Rect,
+    hovered: bool,
+    /// The window the game view was shown in this frame, `None` while it's hidden
+    window: Option<Entity>,
+    /// The image the camera renders to in [`GameViewMode::Texture`]
+    texture: Option<Handle<Image>>,
+    /// Whether overlays registered with [`OverlayAppExt::add_overlay`] are drawn in this view
+    show_overlays: bool,
+}
+
+impl GameViewport {
+    fn new(camera: Entity) -> Self {
+        Self {
+            camera,
+            rect: egui::Rect::NOTHING,
+            hovered: false,
+            window: None,
+            texture: None,
+            show_overlays: true,
+        }
+    }
+}
+
 impl UiState {
-    fn ui(&mut self, world: &mut World, ctx: &mut egui::Context) {
+    /// The game view under the pointer
+    fn hovered_viewport(&self) -> Option<&GameViewport> {
+        self.viewports.iter().find(|viewport| viewport.hovered)
+    }
+
+    /// Camera of the game view that was hovered last
+    fn active_camera(&self) -> Option<Entity> {
+        self.viewports
+            .get(self.active_viewport)
+            .map(|viewport| viewport.camera)
+    }
+
+    fn ui(&mut self, world: &mut World, egui_contexts: &[(Entity, egui::Context)]) {
+        let Ok(editor_window) = world
+            .query_filtered::<Entity, With<Ed2dWindow>>()
+            .get_single(world)
+        else {
+            return;
+        };
+
+        // tabs whose window has been closed go back to the main dock
+        let state = &mut self.state;
+        self.detached_tabs.retain(|(window, tab)| {
+            let open = world.get_entity(*window).is_some();
+            if !open {
+                state.push_to_focused_leaf(tab.clone());
+            }
+            open
+        });
+
+        let egui_context = |window: Entity| {
+            egui_contexts
+                .iter()
+                .find(|(entity, _)| *entity == window)
+                .map(|(_, ctx)| ctx.clone())
+        };
+
+        let mut pop_outs = Vec::new();
+        let mut new_game_view = false;
+        for viewport in &mut self.viewports {
+            viewport.window = None;
+            viewport.hovered = false;
+        }
+
         let mut tab_viewer = TabViewer {
             world,
-            viewport_rect: &mut self.viewport_rect,
-            viewport_hovered: &mut self.viewport_hovered,
+            window: editor_window,
+            viewports: &mut self.viewports,
+            active_viewport: self.active_viewport,
+            snap_to_grid: &mut self.snap_to_grid,
+            new_game_view: &mut new_game_view,
             selected_entities: &mut self.selected_entities,
             selection: &mut self.selection,
+            pop_outs: &mut pop_outs,
             // gizmo_mode: self.gizmo_mode,
         };
-        DockArea::new(&mut self.state)
-            .style(egui_dock::Style::from_egui(ctx.style().as_ref()))
-            .show(ctx, &mut tab_viewer);
+
+        if let Some(ctx) = egui_context(editor_window) {
+            DockArea::new(&mut self.state)
+                .style(egui_dock::Style::from_egui(ctx.style().as_ref()))
+                .show(&ctx, &mut tab_viewer);
+        }
+
+        for (window, tab) in &mut self.detached_tabs {
+            // bevy_egui adds the context the frame after the window is spawned
+            let Some(ctx) = egui_context(*window) else {
+                continue;
+            };
+            tab_viewer.window = *window;
+            let frame = if tab_viewer.clear_background(tab) {
+                egui::Frame::central_panel(ctx.style().as_ref())
+            } else {
+                egui::Frame::none()
+            };
+            egui::CentralPanel::default()
+                .frame(frame)
+                .show(&ctx, |ui| tab_viewer.ui(ui, tab));
+        }
+
+        for tab in pop_outs {
+            if let Some(index) = self.state.find_tab(&tab) {
+                self.state.remove_tab(index);
+                let window = world
+                    .spawn(Window {
+                        title: tab.title(),
+                        ..default()
+                    })
+                    .id();
+                self.detached_tabs.push((window, tab));
+            }
+        }
+
+        if let Some(index) = self.viewports.iter().position(|viewport| viewport.hovered) {
+            self.active_viewport = index;
+        }
+
+        if new_game_view {
+            self.open_game_view(world, editor_window);
+        }
+    }
+
+    /// Opens another game view, reusing the camera of a closed one if there is any
+    fn open_game_view(&mut self, world: &mut World, editor_window: Entity) {
+        let is_open = |index: usize| {
+            let tab = EguiWindow::GameView(index);
+            self.state.find_tab(&tab).is_some()
+                || self
+                    .detached_tabs
+                    .iter()
+                    .any(|(_, detached)| *detached == tab)
+        };
+        let Some(index) = (0..MAX_VIEWPORTS).find(|&index| !is_open(index)) else {
+            return;
+        };
+
+        if index == self.viewports.len() {
+            let target = RenderTarget::Window(WindowRef::Entity(editor_window));
+            let layers = viewport_render_layers(
+                world.resource::<Ed2dCameraSettings>(),
+                *world.resource::<GizmoLayers>(),
+                index,
+                true,
+            );
+            let camera = world.spawn(viewport_camera(index, target, layers)).id();
+            self.viewports.push(GameViewport::new(camera));
+        }
+        self.state.push_to_focused_leaf(EguiWindow::GameView(index));
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq)]
 enum EguiWindow {
-    GameView,
+    /// A game view, by its index in [`UiState::viewports`]
+    GameView(usize),
     Hierarchy,
+    Minimap,
+    Layers,
+    Groups,
+    Tiles,
     Resources,
     Assets,
+    Files,
+    Prefabs,
     Inspector,
+    Log,
+    Diagnostics,
+    Systems,
+    Events,
+    States,
+}
+
+impl EguiWindow {
+    fn title(&self) -> String {
+        match self {
+            EguiWindow::GameView(0) => "GameView".to_string(),
+            EguiWindow::GameView(index) => format!("GameView {}", index + 1),
+            _ => format!("{self:?}"),
+        }
+    }
 }
 
 struct TabViewer<'a> {
     world: &'a mut World,
+    /// The window currently being drawn
+    window: Entity,
     selected_entities: &'a mut SelectedEntities,
     selection: &'a mut InspectorSelection,
-    viewport_rect: &'a mut egui::Rect,
-    viewport_hovered: &'a mut bool,
+    viewports: &'a mut Vec<GameViewport>,
+    active_viewport: usize,
+    snap_to_grid: &'a mut bool,
+    /// Set when another game view should be opened
+    new_game_view: &'a mut bool,
+    pop_outs: &'a mut Vec<EguiWindow>,
     // gizmo_mode: GizmoMode,
 }
 
+impl TabViewer<'_> {
+    /// Replaces the selection with the given entity and shows it in the inspector
+    fn select_entity(&mut self, entity: Entity) {
+        self.selected_entities.select_replace(entity);
+        *self.selection = InspectorSelection::Entities;
+        self.world.send_event(EditorEntitySelectionChanged);
+    }
+
+    /// Converts a position in the game view to world space, snapped to the grid if enabled
+    fn viewport_to_world(&mut self, index: usize, pos: egui::Pos2) -> Option<Vec2> {
+        let viewport = self.viewports.get(index)?;
+        let world_pos = viewport_to_world(self.world, viewport.camera, viewport.rect, pos)?;
+
+        if !*self.snap_to_grid {
+            return Some(world_pos);
+        }
+        let projection = self.world.get::<OrthographicProjection>(viewport.camera)?;
+        let grid_size = grid_size(projection.area.height());
+        Some((world_pos / grid_size).round() * grid_size)
+    }
+
+    fn viewport_toolbar(&mut self, ui: &mut egui::Ui, index: usize) {
+        let camera = self.viewports[index].camera;
+        egui::Frame::menu(ui.style()).show(ui, |ui| {
+            ui.horizontal(|ui| {
+                ui.checkbox(self.snap_to_grid, "Snap to grid")
+                    .on_hover_text("Place dropped assets on the grid");
+                measure::measure_toolbar_ui(self.world, ui);
+                tilemap::paint_toolbar_ui(self.world, ui);
+                overlays::overlays_menu_ui(
+                    self.world,
+                    ui,
+                    &mut self.viewports[index].show_overlays,
+                );
+                camera::navigation_toolbar_ui(self.world, ui, camera);
+                camera::zoom_toolbar_ui(self.world, ui, camera);
+
+                if self.selected_entities.len() > 1 {
+                    if let Some(alignment) = align::align_menu_ui(ui) {
+                        align::align(self.world, self.selected_entities.iter(), alignment);
+                    }
+                }
+                sprites::sprite_toolbar_ui(self.world, ui, self.selected_entities);
+            });
+        });
+    }
+
+    fn game_view_ui(&mut self, ui: &mut egui::Ui, index: usize) {
+        let Some(viewport) = self.viewports.get_mut(index) else {
+            return;
+        };
+        viewport.rect = ui.clip_rect();
+        let response = ui.interact(viewport.rect, ui.id(), Sense::drag());
+        viewport.hovered = response.hovered();
+        viewport.window = Some(self.window);
+        let (camera, rect) = (viewport.camera, viewport.rect);
+
+        if let Some(texture) = &viewport.texture {
+            let mut user_textures = self.world.resource_mut::<EguiUserTextures>();
+            let texture_id = match user_textures.image_id(texture) {
+                Some(texture_id) => texture_id,
+                None => user_textures.add_image(texture.clone()),
+            };
+            let uv = egui::Rect::from_min_max(egui::pos2(0., 0.), egui::pos2(1., 1.));
+            ui.painter()
+                .image(texture_id, rect, uv, egui::Color32::WHITE);
+        }
+
+        camera::camera_controls_ui(self.world, ui, &response, rect, camera);
+        if self.world.resource::<measure::Measurement>().active {
+            measure::measure_ui(self.world, &response, rect, camera);
+        } else if self.world.resource::<tilemap::TilePainter>().active {
+            tilemap::paint_ui(self.world, ui, &response, rect, camera);
+        } else {
+            sprites::sprite_handles_ui(self.world, ui, rect, camera, self.selected_entities);
+        }
+        measure::measurement_label_ui(self.world, ui, rect, camera);
+
+        self.viewport_toolbar(ui, index);
+
+        if let Some(drag) = response.dnd_release_payload::<assets::AssetDrag>() {
+            let position = ui
+                .ctx()
+                .pointer_interact_pos()
+                .and_then(|pos| self.viewport_to_world(index, pos));
+            if let Some(entity) = position
+                .and_then(|position| assets::spawn_dropped_asset(self.world, &drag, position))
+            {
+                self.select_entity(entity);
+            }
+        }
+
+        // draw_gizmo(ui, self.world, self.selected_entities, self.gizmo_mode);
+    }
+}
+
 impl egui_dock::TabViewer for TabViewer<'_> {
     type Tab = EguiWindow;
 
@@ -249,25 +992,66 @@ impl egui_dock::TabViewer for TabViewer<'_> {
         let type_registry = type_registry.read();
 
         match window {
-            EguiWindow::GameView => {
-                *self.viewport_rect = ui.clip_rect();
-                let response = ui.interact(*self.viewport_rect, ui.id(), Sense::hover());
-                *self.viewport_hovered = response.hovered();
-
-                // draw_gizmo(ui, self.world, self.selected_entities, self.gizmo_mode);
-            }
+            EguiWindow::GameView(index) => self.game_view_ui(ui, *index),
             EguiWindow::Hierarchy => {
+                prefabs::save_prefab_ui(self.world, ui, self.selected_entities);
+                ui.separator();
                 let selected = hierarchy_ui(self.world, ui, self.selected_entities);
                 if selected {
                     *self.selection = InspectorSelection::Entities;
                     self.world.send_event(EditorEntitySelectionChanged);
                 }
             }
-            EguiWindow::Resources => select_resource(ui, &type_registry, self.selection),
-            EguiWindow::Assets => select_asset(ui, &type_registry, self.world, self.selection),
+            EguiWindow::Minimap => minimap::minimap_ui(
+                self.world,
+                ui,
+                self.selected_entities,
+                self.viewports,
+                self.active_viewport,
+            ),
+            EguiWindow::Layers => {
+                let selected = layers::layers_ui(self.world, ui, self.selected_entities);
+                if selected {
+                    *self.selection = InspectorSelection::Entities;
+                    self.world.send_event(EditorEntitySelectionChanged);
+                }
+            }
+            EguiWindow::Groups => {
+                let selected =
+                    groups::groups_ui(self.world, ui, &type_registry, self.selected_entities);
+                if selected {
+                    *self.selection = InspectorSelection::Entities;
+                    self.world.send_event(EditorEntitySelectionChanged);
+                }
+            }
+            EguiWindow::Tiles => tilemap::palette_ui(self.world, ui, self.selected_entities),
+            EguiWindow::Log => {
+                if let Some(entity) = console::console_ui(self.world, ui) {
+                    self.select_entity(entity);
+                }
+            }
+            EguiWindow::Diagnostics => diagnostics::diagnostics_ui(self.world, ui),
+            EguiWindow::Systems => systems::systems_ui(self.world, ui),
+            EguiWindow::Events => events::events_ui(self.world, ui),
+            EguiWindow::States => states::states_ui(self.world, ui),
+            EguiWindow::Resources => {
+                resources::resources_ui(self.world, ui, &type_registry, self.selection)
+            }
+            EguiWindow::Assets => assets::assets_ui(self.world, ui, &type_registry, self.selection),
+            EguiWindow::Files => {
+                asset_browser::asset_browser_ui(self.world, ui, &type_registry, self.selection)
+            }
+            EguiWindow::Prefabs => {
+                if let Some(entity) = prefabs::prefabs_ui(self.world, ui, self.selected_entities) {
+                    self.select_entity(entity);
+                }
+            }
             EguiWindow::Inspector => match *self.selection {
                 InspectorSelection::Entities => match self.selected_entities.as_slice() {
-                    &[entity] => ui_for_entity_with_children(self.world, entity, ui),
+                    &[entity] => {
+                        atlas::texture_atlas_ui(self.world, ui, entity);
+                        ui_for_entity_with_children(self.world, entity, ui);
+                    }
                     entities => ui_for_entities_shared_components(self.world, entities, ui),
                 },
                 InspectorSelection::Resource(type_id, ref name) => {
@@ -295,81 +1079,33 @@ impl egui_dock::TabViewer for TabViewer<'_> {
     }
 
     fn title(&mut self, window: &mut Self::Tab) -> egui_dock::egui::WidgetText {
-        format!("{window:?}").into()
+        window.title().into()
     }
 
-    fn clear_background(&self, window: &Self::Tab) -> bool {
-        !matches!(window, EguiWindow::GameView)
+    fn closeable(&mut self, window: &mut Self::Tab) -> bool {
+        // the first game view shows the game while the editor is hidden
+        !matches!(window, EguiWindow::GameView(0))
     }
-}
 
-fn select_resource(
-    ui: &mut egui::Ui,
-    type_registry: &TypeRegistry,
-    selection: &mut InspectorSelection,
-) {
-    let mut resources: Vec<_> = type_registry
-        .iter()
-        .filter(|registration| registration.data::<ReflectResource>().is_some())
-        .map(|registration| {
-            (
-                registration.type_info().type_path_table().short_path(),
-                registration.type_id(),
-            )
-        })
-        .collect();
-    resources.sort_by(|(name_a, _), (name_b, _)| name_a.cmp(name_b));
-
-    for (resource_name, type_id) in resources {
-        let selected = match *selection {
-            InspectorSelection::Resource(selected, _) => selected == type_id,
-            _ => false,
-        };
-
-        if ui.selectable_label(selected, resource_name).clicked() {
-            *selection = InspectorSelection::Resource(type_id, resource_name.to_string());
-        }
+    fn clear_background(&self, window: &Self::Tab) -> bool {
+        !matches!(window, EguiWindow::GameView(_))
     }
-}
 
-fn select_asset(
-    ui: &mut egui::Ui,
-    type_registry: &TypeRegistry,
-    world: &World,
-    selection: &mut InspectorSelection,
-) {
-    let mut assets: Vec<_> = type_registry
-        .iter()
-        .filter_map(|registration| {
-            let reflect_asset = registration.data::<ReflectAsset>()?;
-            Some((
-                registration.type_info().type_path_table().short_path(),
-                registration.type_id(),
-                reflect_asset,
-            ))
-        })
-        .collect();
-    assets.sort_by(|(name_a, ..), (name_b, ..)| name_a.cmp(name_b));
-
-    for (asset_name, asset_type_id, reflect_asset) in assets {
-        let handles: Vec<_> = reflect_asset.ids(world).collect();
-
-        ui.collapsing(format!("{asset_name} ({})", handles.len()), |ui| {
-            for handle in handles {
-                let selected = match *selection {
-                    InspectorSelection::Asset(_, _, selected_id) => selected_id == handle,
-                    _ => false,
-                };
-
-                if ui
-                    .selectable_label(selected, format!("{:?}", handle))
-                    .clicked()
-                {
-                    *selection =
-                        InspectorSelection::Asset(asset_type_id, asset_name.to_string(), handle);
-                }
-            }
-        });
+    fn context_menu(
+        &mut self,
+        ui: &mut egui::Ui,
+        window: &mut Self::Tab,
+        _surface: SurfaceIndex,
+        _node: NodeIndex,
+    ) {
+        if ui.button("Pop out to window").clicked() {
+            self.pop_outs.push(window.clone());
+            ui.close_menu();
+        }
+        if matches!(window, EguiWindow::GameView(_)) && ui.button("New game view").clicked() {
+            *self.new_game_view = true;
+            ui.close_menu();
+        }
     }
 }
 
@@ -425,6 +1161,11 @@ fn is_ui_active(ui_state: Res<UiState>) -> bool {
     ui_state.active
 }
 
+/// Run condition for keyboard shortcuts, which shouldn't fire while typing in a text field
+fn typing(ui_state: Res<UiState>) -> bool {
+    ui_state.typing
+}
+
 fn auto_add_pickables(
     mut commands: Commands,
     query: Query<Entity, (Without<Pickable>, With<Sprite>)>,
@@ -447,13 +1188,16 @@ fn handle_deselect_events(
     }
 }
 
-fn draw_transform_gizmos(
-    mut gizmos: Gizmos,
+fn draw_transform_gizmos<G: ViewportGizmos>(
+    mut gizmos: Gizmos<G>,
     aabbs: Query<(Option<&Aabb>, &GlobalTransform)>,
     ui_state: Res<UiState>,
-    editor_camera: Query<&OrthographicProjection, With<Ed2dCamera>>,
+    editor_cameras: Query<&OrthographicProjection, With<Ed2dCamera>>,
 ) {
-    let Ok(cam_projection) = editor_camera.get_single() else {
+    let Some(viewport) = ui_state.viewports.get(G::VIEWPORT) else {
+        return;
+    };
+    let Ok(cam_projection) = editor_cameras.get(viewport.camera) else {
         return;
     };
 
@@ -462,24 +1206,30 @@ fn draw_transform_gizmos(
 
     for selected_entity in ui_state.selected_entities.iter() {
         if let Ok((aabb, transform)) = aabbs.get(selected_entity) {
-            let (scale, rotation, translation) = transform.to_scale_rotation_translation();
+            let (scale, rotation, _) = transform.to_scale_rotation_translation();
 
             gizmos.axes_2d(*transform, base_length);
 
             if let Some(aabb) = aabb {
+                // the aabb is in the entity's local space, e.g. off center for anchored sprites
+                let center = transform.transform_point(aabb.center.into());
                 let size = scale.xy() * aabb.half_extents.xy() * 2.;
                 let color = palettes::tailwind::NEUTRAL_50;
-                gizmos.rect(translation, rotation, size, color);
+                gizmos.rect(center, rotation, size, color);
             }
         }
     }
 }
 
-fn draw_grid_gizmo(
-    mut gizmos: Gizmos,
-    editor_camera: Query<(&Transform, &OrthographicProjection), With<Ed2dCamera>>,
+fn draw_grid_gizmo<G: ViewportGizmos>(
+    mut gizmos: Gizmos<G>,
+    ui_state: Res<UiState>,
+    editor_cameras: Query<(&Transform, &OrthographicProjection), With<Ed2dCamera>>,
 ) {
-    let Ok((cam_transform, cam_projection)) = editor_camera.get_single() else {
+    let Some(viewport) = ui_state.viewports.get(G::VIEWPORT) else {
+        return;
+    };
+    let Ok((cam_transform, cam_projection)) = editor_cameras.get(viewport.camera) else {
         return;
     };
 
@@ -488,15 +1238,7 @@ fn draw_grid_gizmo(
     let view_height = view_area.height();
     let view_width = view_area.width();
 
-    // let grid_sizes = [
-    //     1., 2., 5., 10., 20., 50., 100., 200., 500., 1000., 2000., 5000.,
-    // ];
-    let grid_sizes = [1., 10., 100., 1000., 10_000.];
-    let grid_size = grid_sizes
-        .iter()
-        .copied()
-        .find(|&size| view_height / size < 50.)
-        .unwrap_or(10_000.);
+    let grid_size = grid_size(view_height);
 
     let cell_count = UVec2::new(
         (view_width / grid_size).ceil() as u32 + 3,
@@ -512,6 +1254,64 @@ fn draw_grid_gizmo(
     gizmos.grid_2d(center, 0., cell_count, Vec2::splat(grid_size), color);
 }
 
+/// Converts a position in a game view to world space, through the game view's camera
+fn viewport_to_world(
+    world: &World,
+    camera: Entity,
+    viewport_rect: egui::Rect,
+    pos: egui::Pos2,
+) -> Option<Vec2> {
+    let viewport_pos = (pos - viewport_rect.min) * game_view_scale_factor(world, camera);
+
+    let camera_transform = world.get::<GlobalTransform>(camera)?;
+    let camera = world.get::<Camera>(camera)?;
+    camera.viewport_to_world_2d(camera_transform, Vec2::new(viewport_pos.x, viewport_pos.y))
+}
+
+/// Converts a world position to a position in a game view, through the game view's camera
+fn world_to_viewport(
+    world: &World,
+    camera: Entity,
+    viewport_rect: egui::Rect,
+    pos: Vec2,
+) -> Option<egui::Pos2> {
+    let scale_factor = game_view_scale_factor(world, camera);
+
+    let camera_transform = world.get::<GlobalTransform>(camera)?;
+    let camera = world.get::<Camera>(camera)?;
+    let viewport_pos = camera.world_to_viewport(camera_transform, pos.extend(0.))?;
+    Some(viewport_rect.min + egui::vec2(viewport_pos.x, viewport_pos.y) / scale_factor)
+}
+
+/// Replaces the selection with `entities`, returns whether there were any
+fn select_entities(
+    selected_entities: &mut SelectedEntities,
+    entities: impl IntoIterator<Item = Entity>,
+) -> bool {
+    let mut entities = entities.into_iter();
+    let Some(first) = entities.next() else {
+        return false;
+    };
+    selected_entities.select_replace(first);
+    for entity in entities {
+        selected_entities.select_maybe_add(entity, true);
+    }
+    true
+}
+
+/// Size of the grid cells, depending on how much of the world is visible
+fn grid_size(view_height: f32) -> f32 {
+    // let grid_sizes = [
+    //     1., 2., 5., 10., 20., 50., 100., 200., 500., 1000., 2000., 5000.,
+    // ];
+    let grid_sizes = [1., 10., 100., 1000., 10_000.];
+    grid_sizes
+        .iter()
+        .copied()
+        .find(|&size| view_height / size < 50.)
+        .unwrap_or(10_000.)
+}
+
 fn add_no_deselect(
     mut commands: Commands,
     egui_context: Query<Entity, (With<EguiContext>, Without<NoDeselect>)>,
@@ -534,7 +1334,10 @@ fn editor_picking(
     {
         if let NormalizedRenderTarget::Window(id) = location.target {
             if let Ok((entity, mut ctx)) = egui_context.get_mut(id.entity()) {
-                if ctx.get_mut().wants_pointer_input() && !ui_state.viewport_hovered {
+                let over_viewport = ui_state
+                    .hovered_viewport()
+                    .is_some_and(|viewport| viewport.window == Some(entity));
+                if ctx.get_mut().wants_pointer_input() && !over_viewport {
                     let entry = (entity, HitData::new(entity, 0.0, None, None));
                     let order = 1_000_000f32; // Assume egui should be on top of everything else.
                     output.send(PointerHits::new(*pointer, Vec::from([entry]), order));
@@ -544,52 +1347,88 @@ fn editor_picking(
     }
 }
 
-/// Syncs UiState picking back to bevy_mod_picking
-fn update_pick_selections(
+/// In [`GameViewMode::Texture`] the cameras don't render to the window the pointer is in, so
+/// pickable entities are hit tested against their bounds through the hovered game view instead.
+fn texture_picking(
+    pointers: Query<(&PointerId, &PointerLocation)>,
     ui_state: Res<UiState>,
-    mut changed_events: EventReader<EditorEntitySelectionChanged>,
-    mut pick_selections: Query<(Entity, &mut PickSelection)>,
+    egui_settings: Res<bevy_egui::EguiSettings>,
+    windows: Query<&Window>,
+    cameras: Query<(&Camera, &GlobalTransform), With<Ed2dCamera>>,
+    pickables: Query<(Entity, &Aabb, &GlobalTransform), With<Pickable>>,
+    mut output: EventWriter<PointerHits>,
 ) {
-    for _ in changed_events.read() {
-        if let Some((mode, target_entity)) = ui_state.selected_entities.last_action() {
-            match mode {
-                SelectionMode::Replace => {
-                    for (e, mut pick_selection) in &mut pick_selections.iter_mut() {
-                        let is_selected = e == target_entity;
-                        if is_selected != pick_selection.is_selected {
-                            pick_selection.is_selected = is_selected;
-                        }
-                    }
-                }
-                SelectionMode::Add => {
-                    // somewhat confusingly `Add` may either remove or add an entity
-                    let is_selected = ui_state.selected_entities.contains(target_entity);
+    if ui_state.game_view_mode != GameViewMode::Texture || !ui_state.active {
+        return;
+    }
+    let Some(viewport) = ui_state.hovered_viewport() else {
+        return;
+    };
+    let camera_entity = viewport.camera;
+    let Ok((camera, camera_transform)) = cameras.get(camera_entity) else {
+        return;
+    };
 
-                    if let Ok((_, mut pick_selection)) = pick_selections.get_mut(target_entity) {
-                        pick_selection.is_selected = is_selected;
-                    }
-                }
-                SelectionMode::Extend => todo!(),
-            }
+    for (pointer, location) in pointers
+        .iter()
+        .filter_map(|(i, p)| p.location.as_ref().map(|l| (i, l)))
+    {
+        let NormalizedRenderTarget::Window(window) = location.target else {
+            continue;
+        };
+        if viewport.window != Some(window.entity()) {
+            continue;
         }
+
+        let Ok(window_scale_factor) = windows.get(window.entity()).map(Window::scale_factor) else {
+            continue;
+        };
+
+        // pointer positions are logical, the camera renders to an image with physical pixels
+        let viewport_min = viewport.rect.min.to_vec2() * egui_settings.scale_factor;
+        let viewport_pos =
+            (location.position - Vec2::new(viewport_min.x, viewport_min.y)) * window_scale_factor;
+        let Some(world_pos) = camera.viewport_to_world_2d(camera_transform, viewport_pos) else {
+            continue;
+        };
+
+        let mut hits: Vec<_> = pickables
+            .iter()
+            .filter_map(|(entity, aabb, transform)| {
+                let z = transform.translation().z;
+                let position = world_pos.extend(z);
+                let local = transform.affine().inverse().transform_point3(position);
+                let offset = (local - Vec3::from(aabb.center)).xy().abs();
+                if offset.cmpgt(Vec3::from(aabb.half_extents).xy()).any() {
+                    return None;
+                }
+                let depth = camera_transform.translation().z - z;
+                let hit = HitData::new(camera_entity, depth, Some(position), None);
+                Some((entity, hit))
+            })
+            .collect();
+        hits.sort_by(|(_, a), (_, b)| a.depth.total_cmp(&b.depth));
+
+        output.send(PointerHits::new(*pointer, hits, camera.order as f32));
     }
 }
 
-fn toggle_pancam(
+/// Syncs UiState picking back to bevy_mod_picking
+fn update_pick_selections(
     ui_state: Res<UiState>,
-    mut pancams: Query<&mut PanCam, With<Ed2dCamera>>,
-    mouse_buttons: Res<ButtonInput<MouseButton>>,
+    mut changed_events: EventReader<EditorEntitySelectionChanged>,
+    mut pick_selections: Query<(Entity, &mut PickSelection)>,
 ) {
-    for mut pancam in &mut pancams.iter_mut() {
-        let hovered = ui_state.viewport_hovered && ui_state.active;
-        if hovered && !pancam.enabled {
-            pancam.enabled = true;
-        }
-        if !hovered
-            && pancam.enabled
-            && !mouse_buttons.any_pressed(pancam.grab_buttons.as_slice().iter().copied())
-        {
-            pancam.enabled = false;
+    if changed_events.read().count() == 0 {
+        return;
+    }
+
+    // tabs may select several entities at once, so sync every pickable rather than only the
+    // last selection action
+    for (entity, mut pick_selection) in &mut pick_selections {
+        let is_selected = ui_state.selected_entities.contains(entity);
+        if is_selected != pick_selection.is_selected {
+            pick_selection.is_selected = is_selected;
         }
     }
 }
@@ -597,38 +1436,33 @@ fn toggle_pancam(
 fn focus_selected_object(
     keys: Res<ButtonInput<KeyCode>>,
     ui_state: Res<UiState>,
-    mut cameras: Query<(&mut Transform, &OrthographicProjection), With<Ed2dCamera>>,
+    mut cameras: Query<(
+        &Transform,
+        &OrthographicProjection,
+        &mut camera::CameraNavigation,
+    )>,
     focusable_entities: Query<&Transform, Without<Ed2dCamera>>,
-    mut target: Local<Option<Vec2>>,
-    time: Res<Time<Real>>,
 ) {
-    if keys.just_pressed(KeyCode::KeyF) && ui_state.viewport_hovered {
-        if let Some(selected) = ui_state.selected_entities.iter().next() {
-            if let Ok(selected_pos) = focusable_entities.get(selected).map(|t| t.translation) {
-                *target = Some(selected_pos.xy());
-            }
-        }
+    if !keys.just_pressed(KeyCode::KeyF) {
+        return;
     }
+    let Some(viewport) = ui_state.hovered_viewport() else {
+        return;
+    };
+    let Some(selected) = ui_state.selected_entities.iter().next() else {
+        return;
+    };
+    let (Ok(selected_transform), Ok((transform, projection, mut navigation))) = (
+        focusable_entities.get(selected),
+        cameras.get_mut(viewport.camera),
+    ) else {
+        return;
+    };
 
-    if let Some(target_pos) = *target {
-        for (mut transform, proj) in &mut cameras.iter_mut() {
-            let view_height = proj.area.height();
-            let snap_distance = view_height * 0.001;
-
-            if Vec2::distance(target_pos, transform.translation.xy()) < snap_distance {
-                // snap the final distance
-                transform.translation.x = target_pos.x;
-                transform.translation.y = target_pos.y;
-                *target = None;
-            } else {
-                let new_pos = transform
-                    .translation
-                    .xy()
-                    .lerp(target_pos, 10. * time.delta_seconds());
-
-                transform.translation.x = new_pos.x;
-                transform.translation.y = new_pos.y;
-            }
-        }
-    }
+    let current = camera::CameraView::new(transform, projection);
+    let target = camera::CameraView {
+        translation: selected_transform.translation.xy(),
+        ..current
+    };
+    navigation.jump(current, target);
 }
@@ -0,0 +1,178 @@
+use crate::{
+    align::entity_bounds, grid_size, tilemap::TilePainter, viewport_to_world, world_to_viewport,
+    Ed2dCamera, UiState, ViewportGizmos,
+};
+use bevy::{color::palettes, prelude::*, render::primitives::Aabb};
+use egui_dock::egui;
+
+/// How close the pointer needs to be to a snap target, in points
+const SNAP_DISTANCE: f32 = 8.;
+
+/// Ruler for measuring distances and angles between two points in the game view
+#[derive(Resource, Default)]
+pub(crate) struct Measurement {
+    /// Whether dragging in the game view measures instead of selecting
+    pub(crate) active: bool,
+    /// Start and end of the ruler in world space, kept until dismissed
+    line: Option<(Vec2, Vec2)>,
+}
+
+/// Run condition for systems that shouldn't react to clicks while measuring
+pub(crate) fn measuring(measurement: Res<Measurement>) -> bool {
+    measurement.active
+}
+
+pub(crate) fn measure_toolbar_ui(world: &mut World, ui: &mut egui::Ui) {
+    let mut measurement = world.resource_mut::<Measurement>();
+    let toggled = ui
+        .toggle_value(&mut measurement.active, "Measure")
+        .on_hover_text("Drag in the game view to measure, click to dismiss")
+        .changed();
+    if measurement.line.is_some() && ui.button("Clear").clicked() {
+        measurement.line = None;
+    }
+    if toggled && measurement.active {
+        world.resource_mut::<TilePainter>().active = false;
+    }
+}
+
+/// Updates the ruler from dragging in the game view
+pub(crate) fn measure_ui(
+    world: &mut World,
+    response: &egui::Response,
+    viewport_rect: egui::Rect,
+    camera: Entity,
+) {
+    let Some(pointer) = response.interact_pointer_pos() else {
+        return;
+    };
+    let Some(position) = snapped_position(world, camera, viewport_rect, pointer) else {
+        return;
+    };
+
+    let mut measurement = world.resource_mut::<Measurement>();
+    if response.drag_started_by(egui::PointerButton::Primary) {
+        measurement.line = Some((position, position));
+    } else if response.dragged_by(egui::PointerButton::Primary) {
+        if let Some((_, end)) = &mut measurement.line {
+            *end = position;
+        }
+    } else if response.drag_stopped_by(egui::PointerButton::Primary) {
+        // clicking without dragging dismisses the ruler
+        if measurement
+            .line
+            .is_some_and(|(start, end)| start.distance(end) < f32::EPSILON)
+        {
+            measurement.line = None;
+        }
+    }
+}
+
+/// Shows the length and angle of the ruler next to it
+pub(crate) fn measurement_label_ui(
+    world: &mut World,
+    ui: &egui::Ui,
+    viewport_rect: egui::Rect,
+    camera: Entity,
+) {
+    let Some((start, end)) = world.resource::<Measurement>().line else {
+        return;
+    };
+    let (Some(screen_start), Some(screen_end)) = (
+        world_to_viewport(world, camera, viewport_rect, start),
+        world_to_viewport(world, camera, viewport_rect, end),
+    ) else {
+        return;
+    };
+
+    let delta = end - start;
+    let angle = delta.y.atan2(delta.x).to_degrees();
+    let text = format!(
+        "{:.1} ({:.1}, {:.1}) {angle:.1}°",
+        delta.length(),
+        delta.x,
+        delta.y
+    );
+
+    let painter = ui.painter_at(viewport_rect);
+    let galley = painter.layout_no_wrap(text, egui::FontId::monospace(12.), egui::Color32::WHITE);
+    let anchor = screen_start + (screen_end - screen_start) / 2. - egui::vec2(0., 6.);
+    let rect = egui::Align2::CENTER_BOTTOM.anchor_size(anchor, galley.size());
+    painter.rect_filled(rect.expand(3.), 2., egui::Color32::from_black_alpha(180));
+    painter.galley(rect.min, galley, egui::Color32::WHITE);
+}
+
+/// Converts the pointer position to world space, snapping to entity centers, bounds corners or
+/// the grid when close enough
+fn snapped_position(
+    world: &mut World,
+    camera: Entity,
+    viewport_rect: egui::Rect,
+    pointer: egui::Pos2,
+) -> Option<Vec2> {
+    let position = viewport_to_world(world, camera, viewport_rect, pointer)?;
+    let snap_distance = viewport_to_world(
+        world,
+        camera,
+        viewport_rect,
+        pointer + egui::vec2(SNAP_DISTANCE, 0.),
+    )?
+    .distance(position);
+
+    let nearest_point = world
+        .query_filtered::<(&Aabb, &GlobalTransform), Without<Ed2dCamera>>()
+        .iter(world)
+        .flat_map(|(aabb, transform)| {
+            let bounds = entity_bounds(aabb, transform);
+            [
+                transform.translation().xy(),
+                bounds.min,
+                bounds.max,
+                Vec2::new(bounds.min.x, bounds.max.y),
+                Vec2::new(bounds.max.x, bounds.min.y),
+            ]
+        })
+        .min_by(|a, b| a.distance(position).total_cmp(&b.distance(position)));
+    if let Some(point) = nearest_point.filter(|point| point.distance(position) < snap_distance) {
+        return Some(point);
+    }
+
+    let view_height = world.get::<OrthographicProjection>(camera)?.area.height();
+    let grid_size = grid_size(view_height);
+    let grid_point = (position / grid_size).round() * grid_size;
+    if grid_point.distance(position) < snap_distance {
+        return Some(grid_point);
+    }
+
+    Some(position)
+}
+
+pub(crate) fn draw_measurement_gizmo<G: ViewportGizmos>(
+    mut gizmos: Gizmos<G>,
+    measurement: Res<Measurement>,
+    ui_state: Res<UiState>,
+    editor_cameras: Query<&OrthographicProjection, With<Ed2dCamera>>,
+) {
+    let Some((start, end)) = measurement.line else {
+        return;
+    };
+    let Some(viewport) = ui_state.viewports.get(G::VIEWPORT) else {
+        return;
+    };
+    let Ok(cam_projection) = editor_cameras.get(viewport.camera) else {
+        return;
+    };
+
+    let color = palettes::tailwind::AMBER_400;
+    let radius = cam_projection.area.height() / 200.;
+
+    gizmos.line_2d(start, end, color);
+    gizmos.circle_2d(start, radius, color);
+    gizmos.circle_2d(end, radius, color);
+
+    // horizontal and vertical legs, to make the angle easier to read
+    let corner = Vec2::new(end.x, start.y);
+    let legs_color = color.with_alpha(0.3);
+    gizmos.line_2d(start, corner, legs_color);
+    gizmos.line_2d(corner, end, legs_color);
+}
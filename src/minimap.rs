@@ -0,0 +1,120 @@
+use crate::{
+    align::entity_bounds,
+    camera::{camera_view, CameraNavigation, CameraView},
+    Ed2dCamera, GameViewport,
+};
+use bevy::{prelude::*, render::primitives::Aabb};
+use bevy_inspector_egui::bevy_inspector::hierarchy::SelectedEntities;
+use egui_dock::egui;
+
+/// Space around the scene, relative to its size
+const MARGIN: f32 = 0.05;
+
+/// Shows the bounds of every entity and the views of the open game views, clicking or dragging
+/// moves the camera of the game view that was hovered last
+pub(crate) fn minimap_ui(
+    world: &mut World,
+    ui: &mut egui::Ui,
+    selected_entities: &SelectedEntities,
+    viewports: &[GameViewport],
+    active_viewport: usize,
+) {
+    let Some(active_camera) = viewports
+        .get(active_viewport)
+        .map(|viewport| viewport.camera)
+    else {
+        return;
+    };
+    let Some(current) = camera_view(world, active_camera) else {
+        return;
+    };
+    let view_rect = |camera: Entity| {
+        let transform = world.get::<Transform>(camera)?;
+        let projection = world.get::<OrthographicProjection>(camera)?;
+        let translation = transform.translation.xy();
+        Some(Rect {
+            min: translation + projection.area.min,
+            max: translation + projection.area.max,
+        })
+    };
+    let Some(view) = view_rect(active_camera) else {
+        return;
+    };
+    // the other game views that are shown
+    let other_views: Vec<Rect> = viewports
+        .iter()
+        .filter(|viewport| viewport.camera != active_camera && viewport.window.is_some())
+        .filter_map(|viewport| view_rect(viewport.camera))
+        .collect();
+
+    let entities: Vec<(Rect, bool)> = world
+        .query_filtered::<(Entity, &Aabb, &GlobalTransform), Without<Ed2dCamera>>()
+        .iter(world)
+        .map(|(entity, aabb, transform)| {
+            (
+                entity_bounds(aabb, transform),
+                selected_entities.contains(entity),
+            )
+        })
+        .collect();
+
+    let scene = entities
+        .iter()
+        .map(|(bounds, _)| bounds)
+        .chain(&other_views)
+        .fold(view, |scene, bounds| scene.union(*bounds));
+    let scene = scene.inflate(scene.size().max_element() * MARGIN);
+
+    let (rect, response) = ui.allocate_exact_size(ui.available_size(), egui::Sense::drag());
+    let scale = (rect.width() / scene.width()).min(rect.height() / scene.height());
+    if !scale.is_finite() || scale <= 0. {
+        return;
+    }
+    let to_screen = |point: Vec2| {
+        let offset = (point - scene.center()) * scale;
+        rect.center() + egui::vec2(offset.x, -offset.y)
+    };
+    let to_screen_rect =
+        |bounds: Rect| egui::Rect::from_two_pos(to_screen(bounds.min), to_screen(bounds.max));
+
+    let painter = ui.painter_at(rect);
+    let visuals = ui.visuals();
+    painter.rect_filled(rect, 0., visuals.extreme_bg_color);
+    for (bounds, selected) in entities {
+        let color = if selected {
+            visuals.selection.bg_fill
+        } else {
+            visuals.widgets.inactive.bg_fill
+        };
+        painter.rect_filled(to_screen_rect(bounds), 0., color);
+    }
+    for other_view in other_views {
+        painter.rect_stroke(
+            to_screen_rect(other_view),
+            0.,
+            egui::Stroke::new(1., visuals.weak_text_color()),
+        );
+    }
+    painter.rect_stroke(
+        to_screen_rect(view),
+        0.,
+        egui::Stroke::new(1.5, visuals.strong_text_color()),
+    );
+
+    let Some(pointer) = response.interact_pointer_pos() else {
+        return;
+    };
+    let offset = (pointer - rect.center()) / scale;
+    let target = CameraView {
+        translation: scene.center() + Vec2::new(offset.x, -offset.y),
+        ..current
+    };
+    let Some(mut navigation) = world.get_mut::<CameraNavigation>(active_camera) else {
+        return;
+    };
+    if response.drag_started() {
+        navigation.jump(current, target);
+    } else if response.dragged() {
+        navigation.move_to(target);
+    }
+}
@@ -0,0 +1,108 @@
+use crate::UiState;
+use bevy::prelude::*;
+use egui_dock::egui;
+use std::any::TypeId;
+
+/// Debug overlays drawn in the game view, toggled from its Overlays menu
+#[derive(Resource, Default)]
+pub(crate) struct Overlays {
+    overlays: Vec<Overlay>,
+    /// Only draw overlays for the selected entities
+    selected_only: bool,
+}
+
+/// Gizmo config group overlays are drawn with, shown in the game views that have overlays enabled
+///
+/// Its [`GizmoConfig`] can be changed like any other group's, except for its render layers.
+#[derive(Default, Reflect, GizmoConfigGroup)]
+pub struct OverlayGizmos;
+
+struct Overlay {
+    name: String,
+    enabled: bool,
+}
+
+pub trait OverlayAppExt {
+    /// Draws gizmos for every entity with the component `C`, e.g. a collider's shape or a velocity
+    /// vector
+    ///
+    /// Overlays can be toggled from the game view's Overlays menu, and limited to the selected
+    /// entities.
+    fn add_overlay<C: Component>(
+        &mut self,
+        name: impl Into<String>,
+        draw: impl Fn(&mut Gizmos<OverlayGizmos>, &C, &GlobalTransform) + Send + Sync + 'static,
+    ) -> &mut Self;
+}
+
+impl OverlayAppExt for App {
+    fn add_overlay<C: Component>(
+        &mut self,
+        name: impl Into<String>,
+        draw: impl Fn(&mut Gizmos<OverlayGizmos>, &C, &GlobalTransform) + Send + Sync + 'static,
+    ) -> &mut Self {
+        self.init_resource::<Overlays>();
+        init_overlay_gizmos(self);
+        let mut overlays = self.world_mut().resource_mut::<Overlays>();
+        let index = overlays.overlays.len();
+        overlays.overlays.push(Overlay {
+            name: name.into(),
+            enabled: true,
+        });
+
+        let draw_overlay =
+            move |mut gizmos: Gizmos<OverlayGizmos>,
+                  overlays: Res<Overlays>,
+                  ui_state: Res<UiState>,
+                  query: Query<(Entity, &C, &GlobalTransform)>| {
+                if !overlays.overlays[index].enabled {
+                    return;
+                }
+                for (entity, component, transform) in &query {
+                    if overlays.selected_only && !ui_state.selected_entities.contains(entity) {
+                        continue;
+                    }
+                    draw(&mut gizmos, component, transform);
+                }
+            };
+        self.add_systems(PostUpdate, draw_overlay)
+    }
+}
+
+/// Adds [`OverlayGizmos`] unless it's already configured, overlays may be added before or after
+/// `Ed2dPlugin`
+///
+/// `Ed2dPlugin` moves the group to its render layer once it's added.
+pub(crate) fn init_overlay_gizmos(app: &mut App) {
+    let configured = app
+        .world()
+        .get_resource::<GizmoConfigStore>()
+        .is_some_and(|store| {
+            store
+                .get_config_dyn(&TypeId::of::<OverlayGizmos>())
+                .is_some()
+        });
+    if !configured {
+        app.insert_gizmo_config(OverlayGizmos, GizmoConfig::default());
+    }
+}
+
+pub(crate) fn overlays_menu_ui(world: &mut World, ui: &mut egui::Ui, show_overlays: &mut bool) {
+    let mut overlays = world.resource_mut::<Overlays>();
+    ui.menu_button("Overlays", |ui| {
+        ui.checkbox(show_overlays, "Show in this view");
+        ui.separator();
+
+        if overlays.overlays.is_empty() {
+            ui.weak("Register overlays with `App::add_overlay`");
+            return;
+        }
+
+        for overlay in &mut overlays.overlays {
+            ui.checkbox(&mut overlay.enabled, &overlay.name);
+        }
+
+        ui.separator();
+        ui.checkbox(&mut overlays.selected_only, "Selected entities only");
+    });
+}
@@ -0,0 +1,604 @@
+use crate::{asset_browser, EditorEntitySelectionChanged, InspectorSelection, UiState};
+use bevy::{
+    asset::{io::AssetSourceId, LoadState, LoadedUntypedAsset, ReflectHandle},
+    ecs::entity::EntityHashMap,
+    prelude::*,
+    reflect::TypeRegistry,
+    tasks::block_on,
+};
+use bevy_inspector_egui::bevy_inspector::hierarchy::SelectedEntities;
+use egui_dock::egui;
+use std::{
+    any::TypeId,
+    path::{Path, PathBuf},
+};
+
+/// Folder in the default asset source that prefabs are saved to
+const PREFAB_FOLDER: &str = "prefabs";
+
+/// Links the root of a spawned prefab to the scene it was spawned from
+#[derive(Component, Reflect, Default)]
+#[reflect(Component)]
+pub struct PrefabInstance {
+    pub prefab: Handle<DynamicScene>,
+}
+
+/// Asset handles of a prefab entity, saved as paths since handles can't be serialized
+///
+/// Replaced with the handle components once the assets have loaded.
+#[derive(Component, Reflect, Default)]
+#[reflect(Component)]
+pub(crate) struct PrefabHandles {
+    handles: Vec<PrefabHandle>,
+    /// Keeps assets that are still loading alive
+    #[reflect(ignore)]
+    loading: Vec<Handle<LoadedUntypedAsset>>,
+}
+
+#[derive(Reflect, Default)]
+pub(crate) struct PrefabHandle {
+    /// Type path of the `Handle<A>` component
+    component: String,
+    path: String,
+}
+
+#[derive(Resource, Default)]
+pub(crate) struct PrefabsPanel {
+    name: String,
+    /// Files in the prefab folder, read when the tab is first shown
+    files: Option<Result<Vec<PathBuf>, String>>,
+    /// Prefabs waiting for their scene to load before being spawned
+    pending: Vec<Handle<DynamicScene>>,
+    status: Option<Result<String, String>>,
+}
+
+enum PrefabAction {
+    Spawn(PathBuf),
+    Revert(Entity),
+    Apply(Entity),
+    UpdateInstances(Entity),
+}
+
+fn prefabs_available(world: &World) -> bool {
+    world.contains_resource::<AssetServer>() && world.contains_resource::<Assets<DynamicScene>>()
+}
+
+fn single_selected(world: &World, selected_entities: &SelectedEntities) -> Option<Entity> {
+    match selected_entities.as_slice() {
+        &[entity] => world.get_entity(entity).map(|_| entity),
+        _ => None,
+    }
+}
+
+/// Name field and "Save as prefab" button, shown in the Hierarchy and Prefabs tabs
+pub(crate) fn save_prefab_ui(
+    world: &mut World,
+    ui: &mut egui::Ui,
+    selected_entities: &SelectedEntities,
+) {
+    if !prefabs_available(world) {
+        return;
+    }
+    let selected = single_selected(world, selected_entities);
+
+    let mut save = None;
+    world.resource_scope::<PrefabsPanel, _>(|world, mut panel| {
+        ui.horizontal(|ui| {
+            let default_name = selected
+                .and_then(|entity| world.get::<Name>(entity))
+                .map(|name| name.as_str())
+                .unwrap_or("prefab");
+            ui.add(egui::TextEdit::singleline(&mut panel.name).hint_text(default_name));
+
+            let response = ui
+                .add_enabled(selected.is_some(), egui::Button::new("Save as prefab"))
+                .on_hover_text("Saves the selected entity and its children")
+                .on_disabled_hover_text("Select a single entity to save it as a prefab");
+            if let (Some(entity), true) = (selected, response.clicked()) {
+                let name = match panel.name.trim() {
+                    "" => default_name.to_string(),
+                    name => name.to_string(),
+                };
+                save = Some((entity, name));
+            }
+        });
+    });
+
+    if let Some((entity, name)) = save {
+        let status = save_prefab(world, entity, &name);
+        world.resource_mut::<PrefabsPanel>().status = Some(status);
+    }
+
+    match &world.resource::<PrefabsPanel>().status {
+        Some(Ok(status)) => {
+            ui.label(status.as_str());
+        }
+        Some(Err(error)) => {
+            ui.colored_label(egui::Color32::RED, error.as_str());
+        }
+        None => {}
+    }
+}
+
+pub(crate) fn prefabs_ui(
+    world: &mut World,
+    ui: &mut egui::Ui,
+    selected_entities: &SelectedEntities,
+) -> Option<Entity> {
+    if !prefabs_available(world) {
+        ui.label("Add `AssetPlugin` and `ScenePlugin` to use prefabs");
+        return None;
+    }
+
+    save_prefab_ui(world, ui, selected_entities);
+
+    let instance =
+        single_selected(world, selected_entities).and_then(|entity| instance_root(world, entity));
+
+    let mut action = None;
+
+    world.resource_scope::<PrefabsPanel, _>(|world, mut panel| {
+        if let Some((root, prefab)) = instance {
+            let asset_server = world.resource::<AssetServer>();
+            let path = asset_server
+                .get_path(prefab.id())
+                .map(|path| path.to_string())
+                .unwrap_or_default();
+
+            ui.horizontal(|ui| {
+                ui.label(format!("Instance of {path}"));
+                if ui
+                    .button("Revert to prefab")
+                    .on_hover_text("Respawns the instance, discarding changes")
+                    .clicked()
+                {
+                    action = Some(PrefabAction::Revert(root));
+                }
+                if ui
+                    .button("Apply overrides")
+                    .on_hover_text("Saves the instance to the prefab")
+                    .clicked()
+                {
+                    action = Some(PrefabAction::Apply(root));
+                }
+            });
+
+            let others = other_instances(world, root, &prefab).len();
+            if others > 0
+                && ui
+                    .button(format!("Update other instances ({others})"))
+                    .on_hover_text(
+                        "Respawns the other instances from the prefab, discarding their changes",
+                    )
+                    .clicked()
+            {
+                action = Some(PrefabAction::UpdateInstances(root));
+            }
+        }
+
+        ui.separator();
+
+        if ui.button("Refresh").clicked() {
+            panel.files = None;
+        }
+
+        let files = panel
+            .files
+            .get_or_insert_with(|| prefab_files(world.resource::<AssetServer>()));
+
+        egui::ScrollArea::vertical()
+            .auto_shrink(false)
+            .show(ui, |ui| match files {
+                Ok(files) if files.is_empty() => {
+                    ui.weak("No prefabs saved yet");
+                }
+                Ok(files) => {
+                    for file in files.iter() {
+                        ui.horizontal(|ui| {
+                            if ui.button("Spawn").clicked() {
+                                action = Some(PrefabAction::Spawn(file.clone()));
+                            }
+                            ui.label(file.display().to_string());
+                        });
+                    }
+                }
+                Err(error) => {
+                    ui.weak(error.as_str());
+                }
+            });
+    });
+
+    let mut spawned = None;
+    let status = match action {
+        Some(PrefabAction::Spawn(path)) => {
+            let prefab = world.resource::<AssetServer>().load(path);
+            world.resource_mut::<PrefabsPanel>().pending.push(prefab);
+            None
+        }
+        Some(PrefabAction::Revert(root)) => Some(revert_prefab(world, root).map(|entity| {
+            spawned = Some(entity);
+            "Reverted to prefab".to_string()
+        })),
+        Some(PrefabAction::Apply(root)) => Some(apply_prefab(world, root)),
+        Some(PrefabAction::UpdateInstances(root)) => Some(update_instances(world, root)),
+        None => None,
+    };
+    if status.is_some() {
+        world.resource_mut::<PrefabsPanel>().status = status;
+    }
+
+    spawned
+}
+
+/// Finds the prefab instance the entity is part of
+fn instance_root(world: &World, entity: Entity) -> Option<(Entity, Handle<DynamicScene>)> {
+    let mut current = entity;
+    loop {
+        if let Some(instance) = world.get::<PrefabInstance>(current) {
+            return Some((current, instance.prefab.clone()));
+        }
+        current = world.get::<Parent>(current)?.get();
+    }
+}
+
+fn prefab_files(asset_server: &AssetServer) -> Result<Vec<PathBuf>, String> {
+    let entries = asset_browser::read_directory(asset_server, Path::new(PREFAB_FOLDER))
+        .map_err(|_| "No prefabs saved yet".to_string())?;
+    Ok(entries
+        .into_iter()
+        .filter(|entry| !entry.is_directory)
+        .map(|entry| entry.path)
+        .filter(|path| path.to_string_lossy().ends_with(".scn.ron"))
+        .collect())
+}
+
+fn collect_subtree(world: &World, entity: Entity, entities: &mut Vec<Entity>) {
+    entities.push(entity);
+    if let Some(children) = world.get::<Children>(entity) {
+        for &child in children {
+            collect_subtree(world, child, entities);
+        }
+    }
+}
+
+fn represents<T: 'static>(value: &dyn Reflect) -> bool {
+    value
+        .get_represented_type_info()
+        .is_some_and(|info| info.type_id() == TypeId::of::<T>())
+}
+
+/// Extracts the entity and its descendants into a scene, with handles replaced by asset paths
+fn prefab_scene(world: &World, root: Entity) -> Result<DynamicScene, String> {
+    let type_registry = world.resource::<AppTypeRegistry>().read();
+    let asset_server = world.resource::<AssetServer>();
+
+    let mut entities = Vec::new();
+    collect_subtree(world, root, &mut entities);
+
+    let mut scene = DynamicSceneBuilder::from_world(world)
+        .deny::<PrefabInstance>()
+        .deny::<PrefabHandles>()
+        .extract_entities(entities.into_iter())
+        .build();
+
+    for entity in &mut scene.entities {
+        // the root's parent isn't part of the prefab
+        if entity.entity == root {
+            entity
+                .components
+                .retain(|component| !represents::<Parent>(component.as_ref()));
+        }
+
+        let mut handles = Vec::new();
+        let mut handle_types = Vec::new();
+        for component in &entity.components {
+            let Some(info) = component.get_represented_type_info() else {
+                continue;
+            };
+            let type_id = info.type_id();
+            let (Some(reflect_handle), Some(reflect_component)) = (
+                type_registry.get_type_data::<ReflectHandle>(type_id),
+                type_registry.get_type_data::<ReflectComponent>(type_id),
+            ) else {
+                continue;
+            };
+            let handle = reflect_component
+                .reflect(world.entity(entity.entity))
+                .and_then(|handle| reflect_handle.downcast_handle_untyped(handle.as_any()))
+                .ok_or("Couldn't read handle")?;
+            let path = asset_server.get_path(handle.id()).ok_or_else(|| {
+                format!(
+                    "{} isn't loaded from a file, so it can't be saved",
+                    info.type_path_table().short_path()
+                )
+            })?;
+            handles.push(PrefabHandle {
+                component: info.type_path().to_string(),
+                path: path.to_string(),
+            });
+            handle_types.push(type_id);
+        }
+
+        if !handles.is_empty() {
+            entity.components.retain(|component| {
+                !component
+                    .get_represented_type_info()
+                    .is_some_and(|info| handle_types.contains(&info.type_id()))
+            });
+            entity.components.push(Box::new(PrefabHandles {
+                handles,
+                loading: Vec::new(),
+            }));
+        }
+    }
+
+    Ok(scene)
+}
+
+/// Serializes the scene to the prefab folder, and updates the loaded prefab if there is one
+fn write_prefab(
+    world: &mut World,
+    scene: DynamicScene,
+    path: &Path,
+) -> Result<Handle<DynamicScene>, String> {
+    let serialized = {
+        let type_registry = world.resource::<AppTypeRegistry>().read();
+        scene
+            .serialize(&type_registry)
+            .map_err(|error| format!("Couldn't serialize prefab: {error}"))?
+    };
+
+    let asset_server = world.resource::<AssetServer>().clone();
+    let writer = asset_server
+        .get_source(AssetSourceId::Default)
+        .map_err(|error| error.to_string())?
+        .writer()
+        .map_err(|error| error.to_string())?;
+    block_on(writer.write_bytes(path, serialized.as_bytes()))
+        .map_err(|error| format!("Couldn't write {}: {error}", path.display()))?;
+
+    let prefab = asset_server.load(path.to_owned());
+    world
+        .resource_mut::<Assets<DynamicScene>>()
+        .insert(prefab.id(), scene);
+    Ok(prefab)
+}
+
+/// Path of the prefab with the name, which has to be a plain file name so it stays in the prefab
+/// folder
+fn prefab_path(name: &str) -> Result<PathBuf, String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err("Prefab name can't be empty".to_string());
+    }
+    if name.contains(['/', '\\']) || name.contains("..") {
+        return Err(format!(
+            "Prefab name \"{name}\" can't contain path separators or \"..\""
+        ));
+    }
+    Ok(Path::new(PREFAB_FOLDER).join(format!("{name}.scn.ron")))
+}
+
+fn save_prefab(world: &mut World, root: Entity, name: &str) -> Result<String, String> {
+    let path = prefab_path(name)?;
+    let scene = prefab_scene(world, root)?;
+    let prefab = write_prefab(world, scene, &path)?;
+
+    world.entity_mut(root).insert(PrefabInstance { prefab });
+    world.resource_mut::<PrefabsPanel>().files = None;
+    Ok(format!("Saved {}", path.display()))
+}
+
+/// Spawns the prefab's entities, returning the root
+fn spawn_prefab(world: &mut World, prefab: &Handle<DynamicScene>) -> Result<Entity, String> {
+    world.resource_scope::<Assets<DynamicScene>, _>(|world, scenes| {
+        let scene = scenes.get(prefab).ok_or("Prefab isn't loaded")?;
+        let root = scene
+            .entities
+            .iter()
+            .find(|entity| {
+                !entity
+                    .components
+                    .iter()
+                    .any(|component| represents::<Parent>(component.as_ref()))
+            })
+            .ok_or("Prefab has no root entity")?
+            .entity;
+
+        let mut entity_map = EntityHashMap::default();
+        scene
+            .write_to_world(world, &mut entity_map)
+            .map_err(|error| format!("Couldn't spawn prefab: {error}"))?;
+
+        let root = entity_map[&root];
+        world.entity_mut(root).insert(PrefabInstance {
+            prefab: prefab.clone(),
+        });
+        Ok(root)
+    })
+}
+
+/// Respawns the instance from its prefab, keeping the root's transform and parent
+fn revert_prefab(world: &mut World, root: Entity) -> Result<Entity, String> {
+    let prefab = world
+        .get::<PrefabInstance>(root)
+        .ok_or("Not a prefab instance")?
+        .prefab
+        .clone();
+    let transform = world.get::<Transform>(root).copied();
+    let parent = world.get::<Parent>(root).map(Parent::get);
+
+    let entity = spawn_prefab(world, &prefab)?;
+    world.entity_mut(root).despawn_recursive();
+
+    let mut entity = world.entity_mut(entity);
+    if let Some(transform) = transform {
+        entity.insert(transform);
+    }
+    if let Some(parent) = parent {
+        entity.set_parent(parent);
+    }
+    Ok(entity.id())
+}
+
+fn instance_prefab(world: &World, root: Entity) -> Result<Handle<DynamicScene>, String> {
+    Ok(world
+        .get::<PrefabInstance>(root)
+        .ok_or("Not a prefab instance")?
+        .prefab
+        .clone())
+}
+
+/// Instances of the prefab other than `root`
+fn other_instances(world: &mut World, root: Entity, prefab: &Handle<DynamicScene>) -> Vec<Entity> {
+    world
+        .query::<(Entity, &PrefabInstance)>()
+        .iter(world)
+        .filter(|(entity, instance)| *entity != root && instance.prefab == *prefab)
+        .map(|(entity, _)| entity)
+        .collect()
+}
+
+/// Saves the instance to its prefab, other instances keep their entities until updated
+fn apply_prefab(world: &mut World, root: Entity) -> Result<String, String> {
+    let prefab = instance_prefab(world, root)?;
+    let path = world
+        .resource::<AssetServer>()
+        .get_path(prefab.id())
+        .ok_or("Prefab has no path")?
+        .path()
+        .to_owned();
+
+    let scene = prefab_scene(world, root)?;
+    write_prefab(world, scene, &path)?;
+    Ok(format!("Saved {}", path.display()))
+}
+
+/// Reverts every other instance of the instance's prefab, which respawns their entities
+fn update_instances(world: &mut World, root: Entity) -> Result<String, String> {
+    let prefab = instance_prefab(world, root)?;
+    let others = other_instances(world, root, &prefab);
+    for &other in &others {
+        revert_prefab(world, other)?;
+    }
+    Ok(format!("Updated {} other instances", others.len()))
+}
+
+/// Spawns prefabs in front of the editor camera once their scene has loaded
+pub(crate) fn spawn_pending_prefabs(world: &mut World) {
+    let pending = std::mem::take(&mut world.resource_mut::<PrefabsPanel>().pending);
+
+    for prefab in pending {
+        let asset_server = world.resource::<AssetServer>();
+        if let Some(LoadState::Failed(error)) = asset_server.get_load_state(prefab.id()) {
+            world.resource_mut::<PrefabsPanel>().status =
+                Some(Err(format!("Couldn't load prefab: {error}")));
+            continue;
+        }
+        if !world
+            .resource::<Assets<DynamicScene>>()
+            .contains(prefab.id())
+        {
+            world.resource_mut::<PrefabsPanel>().pending.push(prefab);
+            continue;
+        }
+
+        let root = match spawn_prefab(world, &prefab) {
+            Ok(root) => root,
+            Err(error) => {
+                world.resource_mut::<PrefabsPanel>().status = Some(Err(error));
+                continue;
+            }
+        };
+
+        let camera_position = world
+            .resource::<UiState>()
+            .active_camera()
+            .and_then(|camera| world.get::<Transform>(camera))
+            .map(|transform| transform.translation.truncate())
+            .unwrap_or_default();
+        if let Some(mut transform) = world.get_mut::<Transform>(root) {
+            transform.translation.x = camera_position.x;
+            transform.translation.y = camera_position.y;
+        }
+
+        let mut ui_state = world.resource_mut::<UiState>();
+        ui_state.selected_entities.select_replace(root);
+        ui_state.selection = InspectorSelection::Entities;
+        world.send_event(EditorEntitySelectionChanged);
+    }
+}
+
+/// Loads the assets of spawned prefabs, and inserts their handles
+pub(crate) fn resolve_prefab_handles(world: &mut World) {
+    let type_registry = world.resource::<AppTypeRegistry>().0.clone();
+    let type_registry = type_registry.read();
+
+    let entities: Vec<_> = world
+        .query_filtered::<Entity, With<PrefabHandles>>()
+        .iter(world)
+        .collect();
+
+    for entity in entities {
+        let Some(mut prefab_handles) = world.entity_mut(entity).take::<PrefabHandles>() else {
+            continue;
+        };
+
+        let asset_server = world.resource::<AssetServer>().clone();
+        let mut unresolved = Vec::new();
+        let mut loading = Vec::new();
+        for prefab_handle in prefab_handles.handles {
+            if let Some(handle) = asset_server.get_handle_untyped(&prefab_handle.path) {
+                insert_handle(world, &type_registry, entity, &prefab_handle, handle);
+                continue;
+            }
+
+            // returns the same handle while the load from the last frame is still alive
+            let loaded = asset_server.load_untyped(prefab_handle.path.clone());
+            if let Some(LoadState::Failed(error)) = asset_server.get_load_state(loaded.id()) {
+                warn!("Couldn't load {}: {error}", prefab_handle.path);
+                continue;
+            }
+            loading.push(loaded);
+            unresolved.push(prefab_handle);
+        }
+
+        if !unresolved.is_empty() {
+            prefab_handles.handles = unresolved;
+            prefab_handles.loading = loading;
+            world.entity_mut(entity).insert(prefab_handles);
+        }
+    }
+}
+
+fn insert_handle(
+    world: &mut World,
+    type_registry: &TypeRegistry,
+    entity: Entity,
+    prefab_handle: &PrefabHandle,
+    handle: UntypedHandle,
+) {
+    let Some(registration) = type_registry.get_with_type_path(&prefab_handle.component) else {
+        warn!("{} isn't registered", prefab_handle.component);
+        return;
+    };
+    let (Some(reflect_handle), Some(reflect_component)) = (
+        registration.data::<ReflectHandle>(),
+        registration.data::<ReflectComponent>(),
+    ) else {
+        return;
+    };
+    if handle.type_id() != reflect_handle.asset_type_id() {
+        warn!(
+            "{} has the wrong asset type for {}",
+            prefab_handle.path, prefab_handle.component
+        );
+        return;
+    }
+    let handle = reflect_handle.typed(handle);
+    reflect_component.insert(
+        &mut world.entity_mut(entity),
+        handle.as_ref(),
+        type_registry,
+    );
+}
@@ -0,0 +1,126 @@
+use crate::{Ed2dCameraSettings, InspectorSelection};
+use bevy::{
+    ecs::reflect::ReflectFromWorld,
+    prelude::*,
+    reflect::{TypeRegistration, TypeRegistry},
+};
+use egui_dock::egui;
+use std::{any::TypeId, collections::BTreeMap};
+
+/// Whether the editor itself relies on the resource, so removing it would panic the editor
+fn is_editor_resource(type_id: TypeId) -> bool {
+    type_id == TypeId::of::<Ed2dCameraSettings>()
+}
+
+enum ResourceAction {
+    Insert(TypeId),
+    Remove(TypeId),
+}
+
+/// Lists reflected resources grouped by module, with buttons to insert missing ones or remove them
+pub(crate) fn resources_ui(
+    world: &mut World,
+    ui: &mut egui::Ui,
+    type_registry: &TypeRegistry,
+    selection: &mut InspectorSelection,
+) {
+    let mut modules: BTreeMap<&str, Vec<_>> = BTreeMap::new();
+    for registration in type_registry.iter() {
+        let Some(reflect_resource) = registration.data::<ReflectResource>() else {
+            continue;
+        };
+        let table = registration.type_info().type_path_table();
+        modules
+            .entry(table.module_path().unwrap_or_default())
+            .or_default()
+            .push((table.short_path(), registration, reflect_resource));
+    }
+
+    let mut action = None;
+
+    for (module, mut resources) in modules {
+        resources.sort_by(|(name_a, ..), (name_b, ..)| name_a.cmp(name_b));
+
+        ui.collapsing(module, |ui| {
+            for (resource_name, registration, reflect_resource) in resources {
+                let type_id = registration.type_id();
+                let exists = reflect_resource.reflect(world).is_some();
+                let selected = match *selection {
+                    InspectorSelection::Resource(selected, _) => selected == type_id,
+                    _ => false,
+                };
+
+                ui.horizontal(|ui| {
+                    let text = if exists {
+                        egui::RichText::new(resource_name)
+                    } else {
+                        egui::RichText::new(resource_name).weak()
+                    };
+                    if ui.selectable_label(selected, text).clicked() {
+                        *selection =
+                            InspectorSelection::Resource(type_id, resource_name.to_string());
+                    }
+
+                    if exists {
+                        if ui
+                            .add_enabled(
+                                !is_editor_resource(type_id),
+                                egui::Button::new("Remove").small(),
+                            )
+                            .on_hover_text("Systems using this resource may panic without it")
+                            .on_disabled_hover_text("The editor needs this resource")
+                            .clicked()
+                        {
+                            action = Some(ResourceAction::Remove(type_id));
+                        }
+                    } else {
+                        let can_insert = registration.data::<ReflectDefault>().is_some()
+                            || registration.data::<ReflectFromWorld>().is_some();
+                        if ui
+                            .add_enabled(can_insert, egui::Button::new("Insert default").small())
+                            .on_disabled_hover_text(
+                                "Register the type with `#[reflect(Default)]` or `#[reflect(FromWorld)]`",
+                            )
+                            .clicked()
+                        {
+                            action = Some(ResourceAction::Insert(type_id));
+                        }
+                    }
+                });
+            }
+        });
+    }
+
+    match action {
+        Some(ResourceAction::Insert(type_id)) => {
+            if let Some(registration) = type_registry.get(type_id) {
+                insert_default(world, registration, type_registry);
+            }
+        }
+        Some(ResourceAction::Remove(type_id)) => {
+            if let Some(reflect_resource) = type_registry.get_type_data::<ReflectResource>(type_id)
+            {
+                reflect_resource.remove(world);
+            }
+        }
+        None => {}
+    }
+}
+
+fn insert_default(
+    world: &mut World,
+    registration: &TypeRegistration,
+    type_registry: &TypeRegistry,
+) {
+    let Some(reflect_resource) = registration.data::<ReflectResource>() else {
+        return;
+    };
+    let resource = if let Some(reflect_default) = registration.data::<ReflectDefault>() {
+        reflect_default.default()
+    } else if let Some(reflect_from_world) = registration.data::<ReflectFromWorld>() {
+        reflect_from_world.from_world(world)
+    } else {
+        return;
+    };
+    reflect_resource.insert(world, resource.as_ref(), type_registry);
+}
@@ -0,0 +1,236 @@
+use crate::{viewport_to_world, world_to_viewport};
+use bevy::{prelude::*, render::primitives::Aabb};
+use bevy_inspector_egui::bevy_inspector::hierarchy::SelectedEntities;
+use egui_dock::egui;
+
+/// Size of the resize handles, in points
+const HANDLE_SIZE: f32 = 8.;
+
+/// Smallest size the handles resize a sprite to
+const MIN_SIZE: f32 = 1.;
+
+/// Where the handles are, relative to the center of the sprite and its half size
+const HANDLES: [Vec2; 8] = [
+    Vec2::new(-1., 1.),
+    Vec2::new(0., 1.),
+    Vec2::new(1., 1.),
+    Vec2::new(1., 0.),
+    Vec2::new(1., -1.),
+    Vec2::new(0., -1.),
+    Vec2::new(-1., -1.),
+    Vec2::new(-1., 0.),
+];
+
+#[derive(Resource, Default)]
+pub(crate) struct SpriteTools {
+    /// Keep the aspect ratio of sprites when resizing them
+    lock_aspect: bool,
+}
+
+/// Size of the sprite before scaling, the image's size unless it has a custom size
+fn sprite_size(world: &World, entity: Entity) -> Option<Vec2> {
+    let sprite = world.get::<Sprite>(entity)?;
+    sprite.custom_size.or_else(|| {
+        let aabb = world.get::<Aabb>(entity)?;
+        Some(Vec3::from(aabb.half_extents).xy() * 2.)
+    })
+}
+
+/// Aspect lock and flip buttons, shown while sprites are selected
+pub(crate) fn sprite_toolbar_ui(
+    world: &mut World,
+    ui: &mut egui::Ui,
+    selected_entities: &SelectedEntities,
+) {
+    let sprites: Vec<Entity> = selected_entities
+        .iter()
+        .filter(|&entity| world.get::<Sprite>(entity).is_some())
+        .collect();
+    if sprites.is_empty() {
+        return;
+    }
+
+    let mut tools = world.resource_mut::<SpriteTools>();
+    ui.toggle_value(&mut tools.lock_aspect, "Lock aspect")
+        .on_hover_text("Keep the aspect ratio when resizing sprites, or hold shift while resizing");
+    let flip_x = ui.button("Flip X").clicked();
+    let flip_y = ui.button("Flip Y").clicked();
+    if !flip_x && !flip_y {
+        return;
+    }
+
+    for entity in sprites {
+        if let Some(mut sprite) = world.get_mut::<Sprite>(entity) {
+            sprite.flip_x ^= flip_x;
+            sprite.flip_y ^= flip_y;
+        }
+    }
+}
+
+/// Edge and corner handles around the selected sprite that resize its `custom_size`
+///
+/// The opposite edge stays in place while resizing, or the sprite's anchor when holding alt.
+pub(crate) fn sprite_handles_ui(
+    world: &mut World,
+    ui: &egui::Ui,
+    viewport_rect: egui::Rect,
+    camera: Entity,
+    selected_entities: &SelectedEntities,
+) {
+    let &[entity] = selected_entities.as_slice() else {
+        return;
+    };
+    let (Some(size), Some(sprite), Some(&global_transform)) = (
+        sprite_size(world, entity),
+        world.get::<Sprite>(entity),
+        world.get::<GlobalTransform>(entity),
+    ) else {
+        return;
+    };
+    let center = -sprite.anchor.as_vec() * size;
+
+    let lock_aspect =
+        world.resource::<SpriteTools>().lock_aspect || ui.input(|input| input.modifiers.shift);
+    let around_anchor = ui.input(|input| input.modifiers.alt);
+
+    let painter = ui.painter_at(viewport_rect);
+    for (index, direction) in HANDLES.into_iter().enumerate() {
+        let position = global_transform
+            .transform_point((center + direction * size / 2.).extend(0.))
+            .xy();
+        let Some(screen_position) = world_to_viewport(world, camera, viewport_rect, position)
+        else {
+            continue;
+        };
+
+        let rect = egui::Rect::from_center_size(screen_position, egui::Vec2::splat(HANDLE_SIZE));
+        let cursor = if direction.y == 0. {
+            egui::CursorIcon::ResizeHorizontal
+        } else if direction.x == 0. {
+            egui::CursorIcon::ResizeVertical
+        } else if direction.x == direction.y {
+            egui::CursorIcon::ResizeNeSw
+        } else {
+            egui::CursorIcon::ResizeNwSe
+        };
+        let response = ui
+            .interact(
+                rect,
+                ui.id().with(("sprite_handle", index)),
+                egui::Sense::drag(),
+            )
+            .on_hover_cursor(cursor);
+        let visuals = ui.style().interact(&response);
+        painter.rect(rect, 1., visuals.bg_fill, visuals.fg_stroke);
+
+        if !response.dragged() {
+            continue;
+        }
+        let Some(pointer) = response
+            .interact_pointer_pos()
+            .and_then(|pointer| viewport_to_world(world, camera, viewport_rect, pointer))
+        else {
+            continue;
+        };
+        let pointer = global_transform
+            .affine()
+            .inverse()
+            .transform_point3(pointer.extend(global_transform.translation().z))
+            .xy();
+        resize(
+            world,
+            entity,
+            size,
+            pointer,
+            direction,
+            lock_aspect,
+            around_anchor,
+        );
+    }
+}
+
+/// Resizes the sprite so the edges of the handle being dragged follow `pointer`, which is in the
+/// sprite's local space
+fn resize(
+    world: &mut World,
+    entity: Entity,
+    size: Vec2,
+    pointer: Vec2,
+    direction: Vec2,
+    lock_aspect: bool,
+    around_anchor: bool,
+) {
+    let Some(anchor) = world
+        .get::<Sprite>(entity)
+        .map(|sprite| sprite.anchor.as_vec())
+    else {
+        return;
+    };
+    let min = -(anchor + 0.5) * size;
+    let max = (0.5 - anchor) * size;
+
+    let mut new_size = size;
+    for axis in 0..2 {
+        let side = direction[axis];
+        if side == 0. {
+            continue;
+        }
+        let length = if around_anchor {
+            // the edge's distance from the anchor is proportional to the size
+            let edge = if side > 0. {
+                0.5 - anchor[axis]
+            } else {
+                -0.5 - anchor[axis]
+            };
+            if edge.abs() < f32::EPSILON {
+                continue;
+            }
+            pointer[axis] / edge
+        } else if side > 0. {
+            pointer[axis] - min[axis]
+        } else {
+            max[axis] - pointer[axis]
+        };
+        new_size[axis] = length.max(MIN_SIZE);
+    }
+
+    if lock_aspect {
+        let scale = new_size / size;
+        let factor = if direction.y == 0. {
+            scale.x
+        } else if direction.x == 0. {
+            scale.y
+        } else {
+            scale.max_element()
+        };
+        new_size = size * factor;
+    }
+
+    // the anchor moves with the resized sprite, unless resizing around it
+    let offset = if around_anchor {
+        Vec2::ZERO
+    } else {
+        let mut new_min = Vec2::ZERO;
+        for axis in 0..2 {
+            let side = direction[axis];
+            new_min[axis] = if side > 0. {
+                min[axis]
+            } else if side < 0. {
+                max[axis] - new_size[axis]
+            } else {
+                (min[axis] + max[axis] - new_size[axis]) / 2.
+            };
+        }
+        new_min + (anchor + 0.5) * new_size
+    };
+
+    if let Some(mut sprite) = world.get_mut::<Sprite>(entity) {
+        sprite.custom_size = Some(new_size);
+    }
+    if offset != Vec2::ZERO {
+        if let Some(mut transform) = world.get_mut::<Transform>(entity) {
+            let offset = transform.rotation * (transform.scale * offset.extend(0.));
+            transform.translation += offset;
+        }
+    }
+}
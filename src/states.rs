@@ -0,0 +1,243 @@
+use bevy::{
+    core::FrameCount,
+    prelude::*,
+    reflect::{
+        DynamicEnum, DynamicVariant, FromType, GetTypeRegistration, TypeInfo, TypeRegistry,
+        VariantInfo,
+    },
+    state::state::FreelyMutableState,
+};
+use egui_dock::egui;
+use std::{any::TypeId, collections::VecDeque};
+
+/// How many transitions are kept per state before dropping the oldest ones
+const MAX_TRANSITIONS: usize = 100;
+
+/// Type data for states shown in the editor's States tab
+///
+/// Register it with [`InspectStateAppExt::inspect_state`], or with `#[reflect(InspectState)]`.
+#[derive(Clone)]
+pub struct ReflectInspectState {
+    /// Formats the current value of `State<S>`, if the state exists
+    current: fn(&World) -> Option<String>,
+    /// Sets `NextState<S>` to the unit variant, computed states can't be set
+    set_next_state: Option<fn(&mut World, &str)>,
+}
+
+impl<S: FreelyMutableState + FromReflect> FromType<S> for ReflectInspectState {
+    fn from_type() -> Self {
+        let set_next_state: fn(&mut World, &str) = |world, variant| {
+            let Some(state) = S::from_reflect(&DynamicEnum::new(variant, DynamicVariant::Unit))
+            else {
+                return;
+            };
+            if let Some(mut next_state) = world.get_resource_mut::<NextState<S>>() {
+                next_state.set(state);
+            }
+        };
+        Self {
+            set_next_state: Some(set_next_state),
+            ..ReflectInspectState::read_only::<S>()
+        }
+    }
+}
+
+impl ReflectInspectState {
+    fn read_only<S: States>() -> Self {
+        Self {
+            current: |world| {
+                world
+                    .get_resource::<State<S>>()
+                    .map(|state| format!("{:?}", state.get()))
+            },
+            set_next_state: None,
+        }
+    }
+}
+
+pub trait InspectStateAppExt {
+    /// Shows the state in the editor's States tab, where the next state can be picked
+    fn inspect_state<S: FreelyMutableState + FromReflect + TypePath + GetTypeRegistration>(
+        &mut self,
+    ) -> &mut Self;
+
+    /// Shows the computed state in the editor's States tab, without picking the next state
+    fn inspect_computed_state<S: States + Reflect + TypePath + GetTypeRegistration>(
+        &mut self,
+    ) -> &mut Self;
+}
+
+impl InspectStateAppExt for App {
+    fn inspect_state<S: FreelyMutableState + FromReflect + TypePath + GetTypeRegistration>(
+        &mut self,
+    ) -> &mut Self {
+        self.register_type::<S>()
+            .register_type_data::<S, ReflectInspectState>()
+    }
+
+    fn inspect_computed_state<S: States + Reflect + TypePath + GetTypeRegistration>(
+        &mut self,
+    ) -> &mut Self {
+        self.register_type::<S>();
+        let type_registry = self.world().resource::<AppTypeRegistry>().clone();
+        if let Some(registration) = type_registry.write().get_mut(TypeId::of::<S>()) {
+            registration.insert(ReflectInspectState::read_only::<S>());
+        }
+        self
+    }
+}
+
+/// States shown in the States tab, with the transitions seen so far
+#[derive(Resource, Default)]
+pub(crate) struct StatesPanel {
+    states: Vec<StateInfo>,
+}
+
+struct StateInfo {
+    name: String,
+    /// Type id of the state type `S`
+    type_id: TypeId,
+    reflect_state: ReflectInspectState,
+    /// Unit variants of `S` that can be picked as the next state
+    variants: Vec<String>,
+    current: Option<String>,
+    transitions: VecDeque<Transition>,
+}
+
+struct Transition {
+    frame: u32,
+    exited: String,
+    entered: String,
+}
+
+/// Adds the states registered since the last call, types can be registered at any time
+fn discover_states(type_registry: &TypeRegistry, states: &mut Vec<StateInfo>) {
+    let mut added = false;
+    for (registration, reflect_state) in type_registry.iter_with_data::<ReflectInspectState>() {
+        let type_id = registration.type_id();
+        if states.iter().any(|state| state.type_id == type_id) {
+            continue;
+        }
+
+        let variants = match registration.type_info() {
+            TypeInfo::Enum(info) => info
+                .iter()
+                .filter(|variant| matches!(variant, VariantInfo::Unit(_)))
+                .map(|variant| variant.name().to_string())
+                .collect(),
+            _ => Vec::new(),
+        };
+        states.push(StateInfo {
+            name: registration
+                .type_info()
+                .type_path_table()
+                .short_path()
+                .to_string(),
+            type_id,
+            reflect_state: reflect_state.clone(),
+            variants,
+            current: None,
+            transitions: VecDeque::new(),
+        });
+        added = true;
+    }
+
+    if added {
+        states.sort_by(|a, b| a.name.cmp(&b.name));
+    }
+}
+
+pub(crate) fn record_state_transitions(world: &mut World) {
+    let type_registry = world.resource::<AppTypeRegistry>().0.clone();
+    let type_registry = type_registry.read();
+    let frame = world.resource::<FrameCount>().0;
+
+    world.resource_scope::<StatesPanel, _>(|world, mut panel| {
+        discover_states(&type_registry, &mut panel.states);
+
+        for state in &mut panel.states {
+            let Some(current) = (state.reflect_state.current)(world) else {
+                continue;
+            };
+            if state.current.as_ref() == Some(&current) {
+                continue;
+            }
+            if let Some(exited) = state.current.replace(current.clone()) {
+                if state.transitions.len() == MAX_TRANSITIONS {
+                    state.transitions.pop_front();
+                }
+                state.transitions.push_back(Transition {
+                    frame,
+                    exited,
+                    entered: current,
+                });
+            }
+        }
+    });
+}
+
+pub(crate) fn states_ui(world: &mut World, ui: &mut egui::Ui) {
+    let mut requested = None;
+    let panel = world.resource::<StatesPanel>();
+
+    if panel.states.is_empty() {
+        ui.label("Register states with `App::inspect_state::<S>()` to see them here");
+        return;
+    }
+
+    egui::ScrollArea::vertical()
+        .auto_shrink(false)
+        .show(ui, |ui| {
+            for state in &panel.states {
+                let current = state.current.as_deref().unwrap_or("-");
+
+                ui.horizontal(|ui| {
+                    ui.strong(state.name.as_str());
+                    match state.reflect_state.set_next_state {
+                        Some(set_next_state) => {
+                            egui::ComboBox::from_id_source(state.type_id)
+                                .selected_text(current)
+                                .show_ui(ui, |ui| {
+                                    for variant in &state.variants {
+                                        let selected = current == variant;
+                                        if ui.selectable_label(selected, variant.as_str()).clicked()
+                                        {
+                                            requested = Some((set_next_state, variant.clone()));
+                                        }
+                                    }
+                                })
+                                .response
+                                .on_hover_text("Sets `NextState`, so transition schedules run");
+                        }
+                        None => {
+                            ui.label(current)
+                                .on_hover_text("Computed states can't be switched from here");
+                        }
+                    }
+                });
+
+                egui::CollapsingHeader::new(format!("Transitions ({})", state.transitions.len()))
+                    .id_source(("transitions", state.type_id))
+                    .show(ui, |ui| {
+                        egui::Grid::new(("transitions_grid", state.type_id))
+                            .striped(true)
+                            .show(ui, |ui| {
+                                for transition in state.transitions.iter().rev() {
+                                    ui.weak(transition.frame.to_string());
+                                    ui.label(format!(
+                                        "{} → {}",
+                                        transition.exited, transition.entered
+                                    ));
+                                    ui.end_row();
+                                }
+                            });
+                    });
+
+                ui.separator();
+            }
+        });
+
+    if let Some((set_next_state, variant)) = requested {
+        set_next_state(world, &variant);
+    }
+}
@@ -0,0 +1,342 @@
+use bevy::{
+    ecs::schedule::{DynEq, InternedScheduleLabel, InternedSystemSet, NodeId, ScheduleGraph},
+    prelude::*,
+    utils::{get_short_name, HashMap, HashSet},
+};
+use egui_dock::egui;
+use std::{any::TypeId, hash::Hasher};
+
+/// Snapshot of the systems in every schedule, shown in the Systems tab
+///
+/// Schedules are taken out of [`Schedules`] while they run, so the snapshot is taken from
+/// [`PreStartup`], [`First`] and [`Last`], which between them see every schedule but `Main`.
+///
+/// Run conditions are moved out of the schedule graph when a schedule is built, so they are
+/// only known for schedules that were inspected before their first run.
+///
+/// Systems are disabled through the named sets they're in: bevy doesn't allow adding run
+/// conditions to a single system, or adding it to another set, once it's in a schedule.
+#[derive(Resource)]
+pub(crate) struct SystemsPanel {
+    schedules: Vec<ScheduleInfo>,
+    /// How many more snapshots to take before the refresh has seen every schedule
+    refreshes_left: u8,
+    /// Sets that have been given the editor's run condition
+    conditioned_sets: HashSet<(InternedScheduleLabel, InternedSystemSet)>,
+    /// Sets that were disabled before their schedule was available to add the run condition
+    pending_sets: Vec<(InternedScheduleLabel, InternedSystemSet)>,
+    filter: String,
+}
+
+impl Default for SystemsPanel {
+    fn default() -> Self {
+        Self {
+            schedules: Vec::new(),
+            refreshes_left: 3,
+            conditioned_sets: HashSet::new(),
+            pending_sets: Vec::new(),
+            filter: String::new(),
+        }
+    }
+}
+
+/// System sets that have been disabled from the Systems tab
+#[derive(Resource, Default)]
+pub(crate) struct DisabledSystemSets(HashSet<InternedSystemSet>);
+
+struct ScheduleInfo {
+    label: InternedScheduleLabel,
+    systems: Vec<SystemInfo>,
+}
+
+struct SystemInfo {
+    id: NodeId,
+    name: String,
+    conditions: Vec<String>,
+    /// Named sets the system is in, directly or through other sets, and how many systems each
+    /// of them contains
+    sets: Vec<(InternedSystemSet, usize)>,
+    runs_before: Vec<String>,
+}
+
+/// Wraps a type-erased set so it can be looked up in bevy's set interner
+///
+/// Schedule graphs only hand out `&dyn SystemSet`, but configuring a set needs its interned
+/// form. Equality and hashing are forwarded, so interning this finds the original set.
+#[derive(Debug)]
+struct ErasedSet(Box<dyn SystemSet>);
+
+impl SystemSet for ErasedSet {
+    fn system_type(&self) -> Option<TypeId> {
+        self.0.system_type()
+    }
+
+    fn is_anonymous(&self) -> bool {
+        self.0.is_anonymous()
+    }
+
+    fn dyn_clone(&self) -> Box<dyn SystemSet> {
+        self.0.dyn_clone()
+    }
+
+    fn as_dyn_eq(&self) -> &dyn DynEq {
+        self.0.as_dyn_eq()
+    }
+
+    fn dyn_hash(&self, state: &mut dyn Hasher) {
+        self.0.dyn_hash(state);
+    }
+}
+
+fn set_enabled(set: InternedSystemSet) -> impl Fn(Res<DisabledSystemSets>) -> bool {
+    move |disabled_sets: Res<DisabledSystemSets>| !disabled_sets.0.contains(&set)
+}
+
+pub(crate) fn inspect_schedules(world: &mut World) {
+    world.resource_scope::<SystemsPanel, _>(|world, mut panel| {
+        let panel = &mut *panel;
+        let mut schedules = world.resource_mut::<Schedules>();
+
+        panel.pending_sets.retain(|&(label, set)| {
+            let Some(schedule) = schedules.get_mut(label) else {
+                return true;
+            };
+            schedule.configure_sets(set.run_if(set_enabled(set)));
+            panel.conditioned_sets.insert((label, set));
+            false
+        });
+
+        if panel.refreshes_left == 0 {
+            return;
+        }
+        panel.refreshes_left -= 1;
+
+        for (_, schedule) in schedules.iter() {
+            let label = schedule.label();
+            let index = panel.schedules.iter().position(|info| info.label == label);
+            let previous = index.map(|index| &panel.schedules[index]);
+            let info = ScheduleInfo {
+                label,
+                systems: system_infos(schedule, previous),
+            };
+            match index {
+                Some(index) => panel.schedules[index] = info,
+                None => panel.schedules.push(info),
+            }
+        }
+
+        panel
+            .schedules
+            .sort_by_cached_key(|info| format!("{:?}", info.label));
+    });
+}
+
+fn system_infos(schedule: &Schedule, previous: Option<&ScheduleInfo>) -> Vec<SystemInfo> {
+    let graph = schedule.graph();
+
+    // systems not built yet are still in the graph, along with their run conditions
+    let mut systems: Vec<_> = graph
+        .systems()
+        .map(|(id, system, conditions)| {
+            let conditions = conditions.iter().map(|c| c.name().to_string()).collect();
+            (id, system.name().to_string(), conditions)
+        })
+        .collect();
+
+    if let Ok(built_systems) = schedule.systems() {
+        systems.extend(built_systems.map(|(id, system)| {
+            let conditions = previous
+                .and_then(|info| info.systems.iter().find(|s| s.id == id))
+                .map(|s| s.conditions.clone())
+                .unwrap_or_default();
+            (id, system.name().to_string(), conditions)
+        }));
+    }
+
+    let hierarchy = graph.hierarchy().graph();
+    let mut sets: HashMap<NodeId, Vec<(InternedSystemSet, usize)>> = HashMap::new();
+    for set_id in hierarchy.nodes() {
+        let Some(set) = graph.get_set_at(set_id) else {
+            continue;
+        };
+        // sets of a single system function can't be configured
+        if set.system_type().is_some() || set.is_anonymous() {
+            continue;
+        }
+        let set = ErasedSet(set.dyn_clone()).intern();
+
+        // systems in nested sets are affected by the set's run conditions too
+        let mut systems_in_set = HashSet::new();
+        let mut visited = HashSet::new();
+        let mut stack = vec![set_id];
+        while let Some(node) = stack.pop() {
+            for child in hierarchy.neighbors(node) {
+                match child {
+                    NodeId::System(_) => {
+                        systems_in_set.insert(child);
+                    }
+                    NodeId::Set(_) => {
+                        if visited.insert(child) {
+                            stack.push(child);
+                        }
+                    }
+                }
+            }
+        }
+        for &system in &systems_in_set {
+            sets.entry(system)
+                .or_default()
+                .push((set, systems_in_set.len()));
+        }
+    }
+
+    let names: HashMap<NodeId, String> = systems
+        .iter()
+        .map(|(id, name, _)| (*id, get_short_name(name)))
+        .collect();
+
+    systems
+        .into_iter()
+        .map(|(id, name, conditions)| SystemInfo {
+            id,
+            name,
+            conditions,
+            sets: sets.remove(&id).unwrap_or_default(),
+            runs_before: graph
+                .dependency()
+                .graph()
+                .neighbors(id)
+                .map(|node| node_name(graph, &names, node))
+                .collect(),
+        })
+        .collect()
+}
+
+fn node_name(graph: &ScheduleGraph, names: &HashMap<NodeId, String>, node: NodeId) -> String {
+    match graph.get_set_at(node) {
+        Some(set) => format!("{set:?}"),
+        None => names.get(&node).cloned().unwrap_or_default(),
+    }
+}
+
+pub(crate) fn systems_ui(world: &mut World, ui: &mut egui::Ui) {
+    world.resource_scope::<SystemsPanel, _>(|world, mut panel| {
+        let panel = &mut *panel;
+        let mut disabled_sets = world.resource_mut::<DisabledSystemSets>();
+
+        ui.horizontal(|ui| {
+            ui.add(egui::TextEdit::singleline(&mut panel.filter).hint_text("Filter"));
+            if ui.button("Refresh").clicked() {
+                panel.refreshes_left = 2;
+            }
+        });
+
+        ui.weak(
+            "Systems are disabled through the named system sets they're in, which disables \
+            every system in the set. Bevy doesn't record run times per system, build with its \
+            `trace` feature and use a tracing profiler such as Tracy to see them.",
+        );
+
+        ui.separator();
+
+        let filter = panel.filter.to_lowercase();
+
+        egui::ScrollArea::vertical()
+            .auto_shrink(false)
+            .show(ui, |ui| {
+                for schedule in &panel.schedules {
+                    let systems: Vec<_> = schedule
+                        .systems
+                        .iter()
+                        .filter(|system| {
+                            filter.is_empty() || system.name.to_lowercase().contains(&filter)
+                        })
+                        .collect();
+
+                    if systems.is_empty() && !filter.is_empty() {
+                        continue;
+                    }
+
+                    egui::CollapsingHeader::new(format!(
+                        "{:?} ({})",
+                        schedule.label,
+                        systems.len()
+                    ))
+                    .id_source(schedule.label)
+                    .show(ui, |ui| {
+                        for system in systems {
+                            let mut disabled = false;
+                            let mut pending = false;
+                            for (set, _) in &system.sets {
+                                if disabled_sets.0.contains(set) {
+                                    if panel.conditioned_sets.contains(&(schedule.label, *set)) {
+                                        disabled = true;
+                                    } else {
+                                        pending = true;
+                                    }
+                                }
+                            }
+                            let mut title = get_short_name(&system.name);
+                            if disabled {
+                                title.push_str(" (disabled)");
+                            } else if pending {
+                                title.push_str(" (disable pending)");
+                            }
+
+                            egui::CollapsingHeader::new(title)
+                                .id_source((schedule.label, system.id))
+                                .show(ui, |ui| {
+                                    ui.weak(system.name.as_str());
+
+                                    if !system.conditions.is_empty() {
+                                        ui.label(format!(
+                                            "Run if: {}",
+                                            system.conditions.join(", ")
+                                        ));
+                                    }
+
+                                    if !system.runs_before.is_empty() {
+                                        ui.label(format!(
+                                            "Runs before: {}",
+                                            system.runs_before.join(", ")
+                                        ));
+                                    }
+
+                                    if system.sets.is_empty() {
+                                        ui.weak(
+                                            "Not in a named system set, so it can't be disabled",
+                                        );
+                                    }
+                                    for &(set, count) in &system.sets {
+                                        let key = (schedule.label, set);
+                                        let mut enabled = !disabled_sets.0.contains(&set);
+                                        let mut text = format!("{set:?} ({count} systems)");
+                                        if !enabled && !panel.conditioned_sets.contains(&key) {
+                                            text.push_str(" (pending)");
+                                        }
+                                        let response =
+                                            ui.checkbox(&mut enabled, text).on_hover_text(
+                                                "Disabling a set skips all systems in it. It \
+                                                stays pending until the editor sees the \
+                                                schedule outside of its run.",
+                                            );
+                                        if response.changed() {
+                                            if enabled {
+                                                disabled_sets.0.remove(&set);
+                                            } else {
+                                                disabled_sets.0.insert(set);
+                                                if !panel.conditioned_sets.contains(&key)
+                                                    && !panel.pending_sets.contains(&key)
+                                                {
+                                                    panel.pending_sets.push(key);
+                                                }
+                                            }
+                                        }
+                                    }
+                                });
+                        }
+                    });
+                }
+            });
+    });
+}
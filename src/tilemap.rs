@@ -0,0 +1,475 @@
+use crate::{
+    assets::{egui_texture, fit},
+    measure::Measurement,
+    viewport_to_world, world_to_viewport,
+};
+use bevy::{prelude::*, utils::HashMap};
+use bevy_inspector_egui::bevy_inspector::{guess_entity_name, hierarchy::SelectedEntities};
+use egui_dock::egui;
+
+/// Size of the tiles in the palette, in points
+const PALETTE_TILE_SIZE: f32 = 32.;
+
+/// Most cells the rectangle tool fills at once, as each one is an entity
+const MAX_RECTANGLE_CELLS: i64 = 10_000;
+
+/// A tile painted in the game view, at a cell of its tilemap's grid
+#[derive(Component, Reflect, Default, Clone, Copy)]
+#[reflect(Component)]
+pub struct Tile {
+    pub cell: IVec2,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum PaintTool {
+    Brush,
+    Erase,
+    Rectangle,
+    Eyedropper,
+}
+
+/// Atlas the palette's tiles come from
+#[derive(Clone)]
+struct Palette {
+    image: Handle<Image>,
+    layout: Handle<TextureAtlasLayout>,
+}
+
+/// Paint mode of the game view, and the tile it paints with
+#[derive(Resource)]
+pub(crate) struct TilePainter {
+    /// Whether clicking in the game view paints instead of selecting
+    pub(crate) active: bool,
+    tool: PaintTool,
+    /// Entity painted tiles are parented to
+    tilemap: Option<Entity>,
+    palette: Option<Palette>,
+    /// Index of the palette's frame to paint with
+    tile: usize,
+    /// Size of the cells in the tilemap's local space
+    tile_size: Vec2,
+    /// Cell the rectangle tool started dragging at
+    rectangle_start: Option<IVec2>,
+}
+
+impl Default for TilePainter {
+    fn default() -> Self {
+        Self {
+            active: false,
+            tool: PaintTool::Brush,
+            tilemap: None,
+            palette: None,
+            tile: 0,
+            tile_size: Vec2::splat(16.),
+            rectangle_start: None,
+        }
+    }
+}
+
+/// Run condition for systems that shouldn't react to clicks while painting
+pub(crate) fn painting(painter: Res<TilePainter>) -> bool {
+    painter.active
+}
+
+/// The Tiles tab, for choosing the tilemap and the tile to paint with
+pub(crate) fn palette_ui(
+    world: &mut World,
+    ui: &mut egui::Ui,
+    selected_entities: &SelectedEntities,
+) {
+    let selected = match selected_entities.as_slice() {
+        &[entity] => Some(entity),
+        _ => None,
+    };
+
+    world.resource_scope::<TilePainter, _>(|world, mut painter| {
+        egui::Grid::new("tilemap_settings")
+            .num_columns(2)
+            .show(ui, |ui| {
+                ui.label("Tilemap");
+                ui.horizontal(|ui| {
+                    match painter
+                        .tilemap
+                        .filter(|&entity| world.get_entity(entity).is_some())
+                    {
+                        Some(tilemap) => ui.label(guess_entity_name(world, tilemap)),
+                        None => ui.weak("None"),
+                    };
+                    if ui
+                        .add_enabled(selected.is_some(), egui::Button::new("Use selected"))
+                        .on_hover_text("Parent painted tiles to the selected entity")
+                        .clicked()
+                    {
+                        painter.tilemap = selected;
+                    }
+                    if ui
+                        .button("New")
+                        .on_hover_text("Spawns an empty tilemap entity")
+                        .clicked()
+                    {
+                        let tilemap = world
+                            .spawn((SpatialBundle::default(), Name::new("Tilemap")))
+                            .id();
+                        painter.tilemap = Some(tilemap);
+                    }
+                });
+                ui.end_row();
+
+                ui.label("Tile size");
+                ui.horizontal(|ui| {
+                    ui.add(egui::DragValue::new(&mut painter.tile_size.x).range(1. ..=f32::MAX));
+                    ui.add(egui::DragValue::new(&mut painter.tile_size.y).range(1. ..=f32::MAX));
+                });
+                ui.end_row();
+            });
+
+        let selected_atlas = selected.and_then(|entity| {
+            Some(Palette {
+                image: world.get::<Handle<Image>>(entity)?.clone(),
+                layout: world.get::<TextureAtlas>(entity)?.layout.clone(),
+            })
+        });
+        if ui
+            .add_enabled(
+                selected_atlas.is_some(),
+                egui::Button::new("Use the selected sprite's atlas"),
+            )
+            .on_disabled_hover_text("Select a sprite with a `TextureAtlas`")
+            .clicked()
+        {
+            let first_frame = selected_atlas.as_ref().and_then(|palette| {
+                let layouts = world.resource::<Assets<TextureAtlasLayout>>();
+                layouts.get(&palette.layout)?.textures.first().copied()
+            });
+            if let Some(frame) = first_frame {
+                painter.tile_size = frame.size().as_vec2();
+            }
+            painter.palette = selected_atlas;
+            painter.tile = 0;
+        }
+
+        ui.separator();
+
+        let Some(palette) = painter.palette.clone() else {
+            ui.weak("Choose an atlas to paint with");
+            return;
+        };
+        let Some(frames) = world
+            .resource::<Assets<TextureAtlasLayout>>()
+            .get(&palette.layout)
+            .map(|layout| layout.textures.clone())
+        else {
+            ui.weak("The atlas layout isn't loaded");
+            return;
+        };
+        let Some((texture_id, image_size)) = egui_texture(world, palette.image.id()) else {
+            ui.weak("The atlas image isn't loaded");
+            return;
+        };
+
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            ui.horizontal_wrapped(|ui| {
+                for (index, frame) in frames.iter().enumerate() {
+                    let uv = egui::Rect::from_min_max(
+                        egui::pos2(
+                            frame.min.x as f32 / image_size.x,
+                            frame.min.y as f32 / image_size.y,
+                        ),
+                        egui::pos2(
+                            frame.max.x as f32 / image_size.x,
+                            frame.max.y as f32 / image_size.y,
+                        ),
+                    );
+                    let image = egui::Image::new((
+                        texture_id,
+                        fit(frame.size().as_vec2(), PALETTE_TILE_SIZE),
+                    ))
+                    .uv(uv);
+                    if ui
+                        .add(egui::ImageButton::new(image).selected(painter.tile == index))
+                        .on_hover_text(format!("Frame {index}"))
+                        .clicked()
+                    {
+                        painter.tile = index;
+                        if painter.tool != PaintTool::Rectangle {
+                            painter.tool = PaintTool::Brush;
+                        }
+                    }
+                }
+            });
+        });
+    });
+}
+
+/// Paint mode toggle and tools
+pub(crate) fn paint_toolbar_ui(world: &mut World, ui: &mut egui::Ui) {
+    let mut painter = world.resource_mut::<TilePainter>();
+    let toggled = ui
+        .toggle_value(&mut painter.active, "Paint")
+        .on_hover_text("Paint tiles from the Tiles tab")
+        .changed();
+    if painter.active {
+        for (tool, name, description) in [
+            (PaintTool::Brush, "Brush", "Click or drag to place tiles"),
+            (PaintTool::Erase, "Erase", "Click or drag to remove tiles"),
+            (
+                PaintTool::Rectangle,
+                "Rectangle",
+                "Drag to fill a rectangle with tiles",
+            ),
+            (
+                PaintTool::Eyedropper,
+                "Eyedropper",
+                "Click a tile to paint with it",
+            ),
+        ] {
+            ui.selectable_value(&mut painter.tool, tool, name)
+                .on_hover_text(description);
+        }
+    }
+
+    // painting and measuring both use dragging in the game view
+    if toggled && painter.active {
+        world.resource_mut::<Measurement>().active = false;
+    }
+}
+
+/// Cell of the tilemap's grid at a world position
+fn cell_at(tilemap: &GlobalTransform, tile_size: Vec2, position: Vec2) -> IVec2 {
+    let local = tilemap
+        .affine()
+        .inverse()
+        .transform_point3(position.extend(tilemap.translation().z))
+        .xy();
+    (local / tile_size).floor().as_ivec2()
+}
+
+/// Tiles of the tilemap by their cell, built once per edit rather than searched for each cell
+fn tile_index(world: &World, tilemap: Entity) -> HashMap<IVec2, Entity> {
+    let Some(children) = world.get::<Children>(tilemap) else {
+        return HashMap::new();
+    };
+    children
+        .iter()
+        .filter_map(|&child| Some((world.get::<Tile>(child)?.cell, child)))
+        .collect()
+}
+
+/// Places the palette's tile at a cell, or removes the tile there if `tile` is `None`
+fn set_tile(
+    world: &mut World,
+    tilemap: Entity,
+    tiles: &mut HashMap<IVec2, Entity>,
+    cell: IVec2,
+    tile_size: Vec2,
+    tile: Option<(&Palette, usize)>,
+) {
+    let translation = (cell.as_vec2() + 0.5) * tile_size;
+    let existing = tiles.get(&cell).copied();
+    match (existing, tile) {
+        (Some(existing), None) => {
+            world.entity_mut(existing).despawn_recursive();
+            tiles.remove(&cell);
+        }
+        (Some(existing), Some((palette, index))) => {
+            let mut entity = world.entity_mut(existing);
+            let unchanged = entity.get::<Handle<Image>>() == Some(&palette.image)
+                && entity
+                    .get::<TextureAtlas>()
+                    .is_some_and(|atlas| atlas.layout == palette.layout && atlas.index == index);
+            if !unchanged {
+                entity.insert((
+                    palette.image.clone(),
+                    TextureAtlas {
+                        layout: palette.layout.clone(),
+                        index,
+                    },
+                ));
+            }
+            // the tile size may have changed since the tile was painted
+            if let Some(mut sprite) = entity.get_mut::<Sprite>() {
+                if sprite.custom_size != Some(tile_size) {
+                    sprite.custom_size = Some(tile_size);
+                }
+            }
+            if let Some(mut transform) = entity.get_mut::<Transform>() {
+                if transform.translation.xy() != translation {
+                    transform.translation = translation.extend(transform.translation.z);
+                }
+            }
+        }
+        (None, Some((palette, index))) => {
+            let entity = world
+                .spawn((
+                    SpriteBundle {
+                        sprite: Sprite {
+                            custom_size: Some(tile_size),
+                            ..default()
+                        },
+                        texture: palette.image.clone(),
+                        transform: Transform::from_translation(translation.extend(0.)),
+                        ..default()
+                    },
+                    TextureAtlas {
+                        layout: palette.layout.clone(),
+                        index,
+                    },
+                    Tile { cell },
+                    Name::new(format!("Tile ({}, {})", cell.x, cell.y)),
+                ))
+                .set_parent(tilemap)
+                .id();
+            tiles.insert(cell, entity);
+        }
+        (None, None) => {}
+    }
+}
+
+/// How many cells are in `min..=max`
+fn cell_count((min, max): (IVec2, IVec2)) -> i64 {
+    let size = max.as_i64vec2() - min.as_i64vec2() + 1;
+    size.x * size.y
+}
+
+/// Corners of cells `min..=max` of the tilemap in the game view
+fn cells_outline(
+    world: &World,
+    viewport_rect: egui::Rect,
+    camera: Entity,
+    tilemap: &GlobalTransform,
+    tile_size: Vec2,
+    (min, max): (IVec2, IVec2),
+) -> Option<Vec<egui::Pos2>> {
+    let (min, max) = (min.as_vec2() * tile_size, (max + 1).as_vec2() * tile_size);
+    [min, Vec2::new(max.x, min.y), max, Vec2::new(min.x, max.y)]
+        .into_iter()
+        .map(|corner| {
+            let position = tilemap.transform_point(corner.extend(0.)).xy();
+            world_to_viewport(world, camera, viewport_rect, position)
+        })
+        .collect()
+}
+
+/// Paints, erases or picks tiles by clicking and dragging in the game view
+pub(crate) fn paint_ui(
+    world: &mut World,
+    ui: &egui::Ui,
+    response: &egui::Response,
+    viewport_rect: egui::Rect,
+    camera: Entity,
+) {
+    let painter = world.resource::<TilePainter>();
+    let (tool, tile_size, palette, tile) = (
+        painter.tool,
+        painter.tile_size,
+        painter.palette.clone(),
+        painter.tile,
+    );
+    let Some(tilemap) = painter
+        .tilemap
+        .filter(|&entity| world.get_entity(entity).is_some())
+    else {
+        return;
+    };
+    let Some(&tilemap_transform) = world.get::<GlobalTransform>(tilemap) else {
+        return;
+    };
+
+    let Some(cell) = response
+        .interact_pointer_pos()
+        .or_else(|| response.hover_pos())
+        .and_then(|pointer| viewport_to_world(world, camera, viewport_rect, pointer))
+        .map(|position| cell_at(&tilemap_transform, tile_size, position))
+    else {
+        return;
+    };
+
+    let rectangle = match world.resource::<TilePainter>().rectangle_start {
+        Some(start) => (start.min(cell), start.max(cell)),
+        None => (cell, cell),
+    };
+    let too_large = cell_count(rectangle) > MAX_RECTANGLE_CELLS;
+    let color = if too_large {
+        ui.visuals().error_fg_color
+    } else {
+        ui.visuals().selection.stroke.color
+    };
+    let viewport_painter = ui.painter_at(viewport_rect);
+    if let Some(points) = cells_outline(
+        world,
+        viewport_rect,
+        camera,
+        &tilemap_transform,
+        tile_size,
+        rectangle,
+    ) {
+        viewport_painter.add(egui::Shape::closed_line(
+            points,
+            egui::Stroke::new(1.5, color),
+        ));
+    }
+    if too_large {
+        if let Some(pointer) = response.interact_pointer_pos() {
+            viewport_painter.text(
+                pointer + egui::vec2(12., 12.),
+                egui::Align2::LEFT_TOP,
+                format!("Too large to fill, at most {MAX_RECTANGLE_CELLS} tiles"),
+                egui::FontId::default(),
+                color,
+            );
+        }
+    }
+
+    let primary = egui::PointerButton::Primary;
+    let tile = palette.as_ref().map(|palette| (palette, tile));
+    match tool {
+        PaintTool::Brush | PaintTool::Erase => {
+            if response.dragged_by(primary) {
+                let tile = if tool == PaintTool::Brush { tile } else { None };
+                if tool == PaintTool::Erase || tile.is_some() {
+                    let mut tiles = tile_index(world, tilemap);
+                    set_tile(world, tilemap, &mut tiles, cell, tile_size, tile);
+                }
+            }
+        }
+        PaintTool::Rectangle => {
+            if response.drag_started_by(primary) {
+                world.resource_mut::<TilePainter>().rectangle_start = Some(cell);
+            } else if response.drag_stopped_by(primary) {
+                world.resource_mut::<TilePainter>().rectangle_start = None;
+                let (min, max) = rectangle;
+                if tile.is_some() && !too_large {
+                    let mut tiles = tile_index(world, tilemap);
+                    for y in min.y..=max.y {
+                        for x in min.x..=max.x {
+                            let cell = IVec2::new(x, y);
+                            set_tile(world, tilemap, &mut tiles, cell, tile_size, tile);
+                        }
+                    }
+                }
+            }
+        }
+        PaintTool::Eyedropper => {
+            if !response.drag_started_by(primary) {
+                return;
+            }
+            let tiles = tile_index(world, tilemap);
+            let Some(picked) = tiles.get(&cell).and_then(|&entity| {
+                Some((
+                    Palette {
+                        image: world.get::<Handle<Image>>(entity)?.clone(),
+                        layout: world.get::<TextureAtlas>(entity)?.layout.clone(),
+                    },
+                    world.get::<TextureAtlas>(entity)?.index,
+                ))
+            }) else {
+                return;
+            };
+            let mut painter = world.resource_mut::<TilePainter>();
+            let (palette, tile) = picked;
+            painter.palette = Some(palette);
+            painter.tile = tile;
+            painter.tool = PaintTool::Brush;
+        }
+    }
+}